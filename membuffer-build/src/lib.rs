@@ -0,0 +1,671 @@
+//!Code generator for membuffer schema files, meant to be called from `build.rs` so several crates
+//!can share one description of a buffer layout. A schema file declares structs with typed
+//!fields:
+//!```text
+//!///An author of articles
+//!struct Author {
+//!    name: string;
+//!    id: u32;
+//!}
+//!
+//!struct Article {
+//!    title: string;
+//!    views: u64;
+//!    samples: [f32];
+//!    tags: [string];
+//!    author: Author;
+//!    reviewers: [Author];
+//!    counts: map<string, u64>;
+//!    summary: string?;
+//!}
+//!```
+//!Every field is stored as one entry in the order of declaration. The supported types are the
+//!scalars `bool`, `char`, `u8` to `u128`, `i8` to `i128`, `f32` and `f64`, `string` and `bytes`,
+//!lists `[T]` of numbers, strings, bytes or structs, maps `map<K, V>` with `string` or `bytes`
+//!keys and scalar, string or bytes values, and other structs of the schema which are stored as
+//!nested buffers. A `?` after the type marks the field as optional, optional fields are stored
+//!as null entries if no value is given. Comments start with `//`, comments starting with `///`
+//!document the following struct or field.
+//!
+//!For every struct `Foo` the generated code contains a `FooBuilder` with one setter per field
+//!which writes the buffer with `to_mem_buffer_writer` or `finalize`, and a `FooView` which checks
+//!the header of a buffer and the value types of its maps against the layout once when it is
+//!opened and loads every field zero copy through its own accessor. Nested buffers are checked
+//!when their accessor opens them. Lists of structs are loaded as `FooListView`, therefore a schema
+//!cannot declare both `Foo` and `FooList`. The generated code uses the `membuffer` crate which has
+//!to be a dependency of the crate including it.
+//!
+//!Compile the schema in the `main` function of `build.rs`:
+//!```no_run
+//!membuffer_build::compile("schema/article.mbs").unwrap();
+//!```
+//!and include the generated code, which is named after the schema file, in the crate:
+//!```ignore
+//!include!(concat!(env!("OUT_DIR"), "/article.rs"));
+//!
+//!let data = ArticleBuilder::new().title("Membuffer").views(10).finalize()?;
+//!let view = ArticleView::new(&data)?;
+//!assert_eq!(view.title()?, "Membuffer");
+//!```
+
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+///Error returned when a schema cannot be compiled
+#[derive(Debug)]
+pub enum Error {
+    ///The schema file could not be read or the generated code could not be written
+    Io(std::io::Error),
+    ///The schema is invalid at the given line, lines are counted from one
+    Parse { line: usize, message: String },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::Io(err) => write!(f,"Membuffer schema error: {}",err),
+            Error::Parse { line, message } => write!(f,"Membuffer schema error: Line {}: {}",line,message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            Error::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Error {
+        Error::Io(err)
+    }
+}
+
+fn parse_error<T>(line: usize, message: String) -> Result<T,Error> {
+    Err(Error::Parse { line, message })
+}
+
+///Scalar types of the schema language with the name of their entry type
+const SCALARS: &[(&str,&str)] = &[
+    ("bool", "Bool"),
+    ("char", "Char"),
+    ("u8", "UInteger8"),
+    ("u16", "UInteger16"),
+    ("u32", "UInteger32"),
+    ("u64", "UInteger64"),
+    ("u128", "UInteger128"),
+    ("i8", "Integer8"),
+    ("i16", "Integer16"),
+    ("i32", "Integer32"),
+    ("i64", "Integer64"),
+    ("i128", "Integer128"),
+    ("f32", "Float32"),
+    ("f64", "Float64"),
+];
+
+///Element types of number lists with the name of their entry type, lists of u8 are `bytes`
+const NUMBER_LISTS: &[(&str,&str)] = &[
+    ("u16", "VectorU16"),
+    ("u32", "VectorU32"),
+    ("u64", "VectorU64"),
+    ("u128", "VectorU128"),
+    ("i8", "VectorI8"),
+    ("i16", "VectorI16"),
+    ("i32", "VectorI32"),
+    ("i64", "VectorI64"),
+    ("i128", "VectorI128"),
+    ("f32", "VectorF32"),
+    ("f64", "VectorF64"),
+];
+
+//Fields cannot be named like the methods generated for every struct
+const RESERVED_NAMES: &[&str] = &["new", "from_reader", "reader", "finalize", "to_mem_buffer_writer"];
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "static", "struct", "super", "trait", "true", "type", "unsafe",
+    "use", "where", "while", "abstract", "become", "box", "do", "final", "macro", "override", "priv",
+    "try", "typeof", "unsized", "virtual", "yield",
+];
+
+///Type of a field in a schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Type {
+    ///One of the scalars, e. g. `u64`
+    Scalar(&'static str),
+    String,
+    Bytes,
+    ///A list of numbers, strings, bytes or structs
+    List(Box<Type>),
+    ///A map with string or byte keys
+    Map(Box<Type>,Box<Type>),
+    ///Another struct of the schema stored as nested buffer
+    Struct(String),
+}
+
+///Field of a struct in a schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    pub doc: Vec<String>,
+    pub field_type: Type,
+    pub optional: bool,
+}
+
+///Struct of a schema, every field is stored as one entry in the order of declaration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Struct {
+    pub name: String,
+    pub doc: Vec<String>,
+    pub fields: Vec<Field>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Punct(char),
+    Doc(String),
+}
+
+fn tokenize(source: &str) -> Result<Vec<(Token,usize)>,Error> {
+    let mut tokens = Vec::new();
+    for (line,text) in source.lines().enumerate() {
+        let line = line + 1;
+        let mut chars = text.char_indices().peekable();
+        while let Some((start,c)) = chars.next() {
+            if c.is_whitespace() {
+                continue;
+            }
+            if text[start..].starts_with("///") {
+                tokens.push((Token::Doc(text[start+3..].trim().to_string()), line));
+                break;
+            }
+            if text[start..].starts_with("//") {
+                break;
+            }
+            if c.is_ascii_alphabetic() || c == '_' {
+                let mut end = start + 1;
+                while let Some(&(x,c)) = chars.peek() {
+                    if !c.is_ascii_alphanumeric() && c != '_' {
+                        break;
+                    }
+                    end = x + 1;
+                    chars.next();
+                }
+                tokens.push((Token::Ident(text[start..end].to_string()), line));
+            } else if "{}:;[]<>,?".contains(c) {
+                tokens.push((Token::Punct(c), line));
+            } else {
+                return parse_error(line, format!("unexpected character {:?}", c));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token,usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn line(&self) -> usize {
+        self.tokens.get(self.pos).or_else(|| self.tokens.last()).map(|x| x.1).unwrap_or(1)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|x| x.0.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|x| &x.0)
+    }
+
+    fn ident(&mut self, what: &str) -> Result<String,Error> {
+        let line = self.line();
+        match self.next() {
+            Some(Token::Ident(ident)) => Ok(ident),
+            _ => parse_error(line, format!("expected {}", what)),
+        }
+    }
+
+    fn expect(&mut self, punct: char) -> Result<(),Error> {
+        let line = self.line();
+        match self.next() {
+            Some(Token::Punct(x)) if x == punct => Ok(()),
+            _ => parse_error(line, format!("expected `{}`", punct)),
+        }
+    }
+
+    fn eat(&mut self, punct: char) -> bool {
+        if self.peek() == Some(&Token::Punct(punct)) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn docs(&mut self) -> Vec<String> {
+        let mut docs = Vec::new();
+        while let Some(Token::Doc(doc)) = self.peek() {
+            docs.push(doc.clone());
+            self.pos += 1;
+        }
+        docs
+    }
+
+    fn field_type(&mut self) -> Result<Type,Error> {
+        let line = self.line();
+        if self.eat('[') {
+            let element = self.field_type()?;
+            self.expect(']')?;
+            return match element {
+                Type::Scalar("u8") => Ok(Type::Bytes),
+                Type::Scalar(x) if NUMBER_LISTS.iter().all(|y| y.0 != x) => parse_error(line, format!("lists of {} are not supported", x)),
+                Type::List(_) | Type::Map(_,_) => parse_error(line, String::from("lists can only hold numbers, strings, bytes or structs")),
+                element => Ok(Type::List(Box::new(element))),
+            };
+        }
+        let name = self.ident("a type")?;
+        match name.as_str() {
+            "string" => Ok(Type::String),
+            "bytes" => Ok(Type::Bytes),
+            "map" => {
+                self.expect('<')?;
+                let key = self.field_type()?;
+                self.expect(',')?;
+                let value = self.field_type()?;
+                self.expect('>')?;
+                if key != Type::String && key != Type::Bytes {
+                    return parse_error(line, String::from("map keys must be string or bytes"));
+                }
+                match value {
+                    Type::Scalar(_) | Type::String | Type::Bytes => Ok(Type::Map(Box::new(key),Box::new(value))),
+                    _ => parse_error(line, String::from("map values must be scalars, string or bytes")),
+                }
+            }
+            _ => match SCALARS.iter().find(|x| x.0 == name) {
+                Some(scalar) => Ok(Type::Scalar(scalar.0)),
+                None => Ok(Type::Struct(name)),
+            }
+        }
+    }
+
+    ///Parses a struct after the `struct` keyword, returns the struct and the line of every field
+    fn parse_struct(&mut self, doc: Vec<String>) -> Result<(Struct,Vec<usize>),Error> {
+        let line = self.line();
+        let name = self.ident("a struct name")?;
+        if !name.starts_with(|x: char| x.is_ascii_uppercase()) {
+            return parse_error(line, format!("struct name {} must start with an upper case letter", name));
+        }
+        self.expect('{')?;
+        let mut fields: Vec<Field> = Vec::new();
+        let mut lines = Vec::new();
+        loop {
+            let doc = self.docs();
+            if self.eat('}') {
+                break;
+            }
+            let line = self.line();
+            let field = self.ident("a field name or `}`")?;
+            if !field.starts_with(|x: char| x.is_ascii_lowercase()) {
+                return parse_error(line, format!("field name {} must start with a lower case letter", field));
+            }
+            if KEYWORDS.contains(&field.as_str()) || RESERVED_NAMES.contains(&field.as_str()) {
+                return parse_error(line, format!("{} cannot be used as field name", field));
+            }
+            if fields.iter().any(|x| x.name == field) {
+                return parse_error(line, format!("field {} is declared twice", field));
+            }
+            self.expect(':')?;
+            let field_type = self.field_type()?;
+            let optional = self.eat('?');
+            self.expect(';')?;
+            fields.push(Field { name: field, doc, field_type, optional });
+            lines.push(line);
+        }
+        Ok((Struct { name, doc, fields },lines))
+    }
+}
+
+///Returns the struct referenced by the type, also looking into lists
+fn referenced_struct(field_type: &Type) -> Option<&str> {
+    match field_type {
+        Type::Struct(name) => Some(name),
+        Type::List(element) => referenced_struct(element),
+        _ => None,
+    }
+}
+
+///Names of the types generated for the struct
+fn generated_types(name: &str) -> [String; 3] {
+    [format!("{}Builder", name), format!("{}View", name), format!("{}ListView", name)]
+}
+
+///Parses a schema and checks that every referenced struct is declared and that no two structs
+///generate a type of the same name, e. g. `Foo` and `FooList` which both generate `FooListView`
+pub fn parse(source: &str) -> Result<Vec<Struct>,Error> {
+    let mut parser = Parser { tokens: tokenize(source)?, pos: 0 };
+    let mut structs: Vec<(Struct,Vec<usize>)> = Vec::new();
+    while parser.peek().is_some() {
+        let doc = parser.docs();
+        if parser.peek().is_none() {
+            break;
+        }
+        let line = parser.line();
+        if parser.ident("`struct`")? != "struct" {
+            return parse_error(line, String::from("expected `struct`"));
+        }
+        let (parsed,lines) = parser.parse_struct(doc)?;
+        if structs.iter().any(|x| x.0.name == parsed.name) {
+            return parse_error(line, format!("struct {} is declared twice", parsed.name));
+        }
+        let generated = generated_types(&parsed.name);
+        for (other,_) in &structs {
+            if let Some(clash) = generated_types(&other.name).iter().find(|x| generated.contains(x)) {
+                return parse_error(line, format!("struct {} generates the type {} which struct {} generates as well", parsed.name, clash, other.name));
+            }
+        }
+        structs.push((parsed,lines));
+    }
+    for (parsed,lines) in &structs {
+        for (field,line) in parsed.fields.iter().zip(lines) {
+            if let Some(name) = referenced_struct(&field.field_type) {
+                if structs.iter().all(|x| x.0.name != name) {
+                    return parse_error(*line, format!("field {} of {} has unknown type {}", field.name, parsed.name, name));
+                }
+            }
+        }
+    }
+    Ok(structs.into_iter().map(|x| x.0).collect())
+}
+
+///Name of the entry type the field is stored with
+fn entry_type(field_type: &Type) -> &'static str {
+    match field_type {
+        Type::Scalar(x) => SCALARS.iter().find(|y| y.0 == *x).map(|y| y.1).unwrap(),
+        Type::String => "Text",
+        Type::Bytes => "VectorU8",
+        Type::List(element) => match &**element {
+            Type::Scalar(x) => NUMBER_LISTS.iter().find(|y| y.0 == *x).map(|y| y.1).unwrap(),
+            Type::String => "TextList",
+            Type::Bytes => "BytesList",
+            _ => "BufferArray",
+        },
+        Type::Map(_,_) => "HashMap",
+        Type::Struct(_) => "MemBuffer",
+    }
+}
+
+///Type the value of the field is given to the builder as
+fn builder_type(field_type: &Type) -> String {
+    match field_type {
+        Type::Scalar(x) => x.to_string(),
+        Type::String => String::from("&'a str"),
+        Type::Bytes => String::from("&'a [u8]"),
+        Type::List(element) => match &**element {
+            Type::Struct(name) => format!("{}Builder<'a>", name),
+            element => format!("&'a [{}]", builder_type(element)),
+        },
+        Type::Map(key,value) => format!("({}, {})", builder_type(key), builder_type(value)),
+        Type::Struct(name) => format!("{}Builder<'a>", name),
+    }
+}
+
+///Type the builder stores the value of the field as
+fn stored_type(field_type: &Type) -> String {
+    match field_type {
+        Type::List(element) if referenced_struct(element).is_some() => format!("Vec<{}>", builder_type(field_type)),
+        Type::Map(_,_) => format!("Vec<{}>", builder_type(field_type)),
+        Type::Struct(_) => format!("Box<{}>", builder_type(field_type)),
+        _ => builder_type(field_type),
+    }
+}
+
+///Type the view loads the field as
+fn view_type(field_type: &Type) -> String {
+    match field_type {
+        Type::Scalar(x) => x.to_string(),
+        Type::String => String::from("&'a str"),
+        Type::Bytes => String::from("&'a [u8]"),
+        Type::List(element) => match &**element {
+            Type::Scalar(x) => format!("::std::borrow::Cow<'a,[{}]>", x),
+            Type::String => String::from("::membuffer::StrList<'a>"),
+            Type::Bytes => String::from("::membuffer::BytesList<'a>"),
+            element => format!("{}ListView<'a>", referenced_struct(element).unwrap()),
+        },
+        Type::Map(_,_) => String::from("::membuffer::MemBufferMap<'a>"),
+        Type::Struct(name) => format!("{}View<'a>", name),
+    }
+}
+
+fn write_doc(out: &mut String, indent: &str, doc: &[String]) {
+    for line in doc {
+        writeln!(out, "{}///{}", indent, line).unwrap();
+    }
+}
+
+///Writes the documentation of a struct followed by an empty line if there is any
+fn write_struct_doc(out: &mut String, doc: &[String]) {
+    write_doc(out, "", doc);
+    if !doc.is_empty() {
+        writeln!(out, "///").unwrap();
+    }
+}
+
+fn generate_builder(out: &mut String, def: &Struct) {
+    let name = &def.name;
+    write_struct_doc(out, &def.doc);
+    writeln!(out, "///Builder writing buffers with the layout of `{}`, see `{}View`", name, name).unwrap();
+    writeln!(out, "#[derive(Default)]").unwrap();
+    writeln!(out, "pub struct {}Builder<'a> {{", name).unwrap();
+    for field in &def.fields {
+        writeln!(out, "    {}: Option<{}>,", field.name, stored_type(&field.field_type)).unwrap();
+    }
+    writeln!(out, "    _lifetime: ::std::marker::PhantomData<&'a ()>,").unwrap();
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(out, "impl<'a> {}Builder<'a> {{", name).unwrap();
+    writeln!(out, "    pub fn new() -> {}Builder<'a> {{", name).unwrap();
+    writeln!(out, "        Default::default()").unwrap();
+    writeln!(out, "    }}").unwrap();
+    for field in &def.fields {
+        writeln!(out).unwrap();
+        write_doc(out, "    ", &field.doc);
+        let value = builder_type(&field.field_type);
+        let store = match &field.field_type {
+            Type::List(element) if referenced_struct(element).is_some() => {
+                writeln!(out, "    pub fn {}<I: IntoIterator<Item={}>>(&mut self, values: I) -> &mut Self {{", field.name, value).unwrap();
+                "values.into_iter().collect()"
+            }
+            Type::Map(_,_) => {
+                writeln!(out, "    pub fn {}<I: IntoIterator<Item={}>>(&mut self, pairs: I) -> &mut Self {{", field.name, value).unwrap();
+                "pairs.into_iter().collect()"
+            }
+            Type::Struct(_) => {
+                writeln!(out, "    pub fn {}(&mut self, value: {}) -> &mut Self {{", field.name, value).unwrap();
+                "Box::new(value)"
+            }
+            _ => {
+                writeln!(out, "    pub fn {}(&mut self, value: {}) -> &mut Self {{", field.name, value).unwrap();
+                "value"
+            }
+        };
+        writeln!(out, "        self.{} = Some({});", field.name, store).unwrap();
+        writeln!(out, "        self").unwrap();
+        writeln!(out, "    }}").unwrap();
+    }
+
+    writeln!(out).unwrap();
+    writeln!(out, "    ///Writes every field as one entry in the order of declaration, fails with `MissingField` if").unwrap();
+    writeln!(out, "    ///a required field was not set").unwrap();
+    writeln!(out, "    pub fn to_mem_buffer_writer(&self) -> ::std::result::Result<::membuffer::MemBufferWriter,::membuffer::MemBufferError> {{").unwrap();
+    writeln!(out, "        let mut writer = ::membuffer::MemBufferWriter::new();").unwrap();
+    for field in &def.fields {
+        let write = match &field.field_type {
+            Type::List(element) if referenced_struct(element).is_some() => String::from("writer.add_buffer_array(value.iter().map(|x| x.to_mem_buffer_writer()).collect::<::std::result::Result<Vec<_>,_>>()?)"),
+            Type::Map(_,_) => String::from("writer.add_map(value.iter().copied())?"),
+            Type::Struct(_) => String::from("writer.add_entry(value.to_mem_buffer_writer()?)"),
+            _ => String::from("writer.add_entry(*value)"),
+        };
+        let missing = if field.optional {
+            String::from("writer.add_entry(None::<&str>)")
+        } else {
+            format!("return Err(::membuffer::MemBufferError::MissingField {{ name: \"{}\" }})", field.name)
+        };
+        writeln!(out, "        match &self.{} {{", field.name).unwrap();
+        writeln!(out, "            Some(value) => {},", write).unwrap();
+        writeln!(out, "            None => {},", missing).unwrap();
+        writeln!(out, "        }}").unwrap();
+    }
+    writeln!(out, "        Ok(writer)").unwrap();
+    writeln!(out, "    }}\n").unwrap();
+    writeln!(out, "    pub fn finalize(&self) -> ::std::result::Result<Vec<u8>,::membuffer::MemBufferError> {{").unwrap();
+    writeln!(out, "        self.to_mem_buffer_writer()?.finalize()").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}\n").unwrap();
+}
+
+fn generate_view(out: &mut String, def: &Struct) {
+    let name = &def.name;
+    write_struct_doc(out, &def.doc);
+    writeln!(out, "///Zero copy view on a buffer with the layout of `{}`, the header is checked against the", name).unwrap();
+    writeln!(out, "///layout once when the view is created").unwrap();
+    writeln!(out, "#[derive(Debug)]").unwrap();
+    writeln!(out, "pub struct {}View<'a> {{", name).unwrap();
+    writeln!(out, "    reader: ::membuffer::MemBufferReader<'a>,").unwrap();
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(out, "impl ::membuffer::Schema for {}View<'_> {{", name).unwrap();
+    writeln!(out, "    fn fields() -> Vec<::membuffer::FieldInfo> {{").unwrap();
+    writeln!(out, "        vec![").unwrap();
+    for (index,field) in def.fields.iter().enumerate() {
+        writeln!(out, "            ::membuffer::FieldInfo {{ index: {}, type_id: ::membuffer::MemBufferTypes::{} as i32, nullable: {}, optional: {} }},",
+            index, entry_type(&field.field_type), field.optional, field.optional).unwrap();
+    }
+    writeln!(out, "        ]").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(out, "impl<'a> {}View<'a> {{", name).unwrap();
+    writeln!(out, "    ///Opens the buffer and checks the types of all entries against the layout").unwrap();
+    writeln!(out, "    pub fn new(data: &'a [u8]) -> ::std::result::Result<{}View<'a>,::membuffer::MemBufferError> {{", name).unwrap();
+    writeln!(out, "        {}View::from_reader(::membuffer::MemBufferReader::new(data)?)", name).unwrap();
+    writeln!(out, "    }}\n").unwrap();
+    writeln!(out, "    ///Checks the types of all entries of the reader and the value types of maps against the layout").unwrap();
+    writeln!(out, "    pub fn from_reader(reader: ::membuffer::MemBufferReader<'a>) -> ::std::result::Result<{}View<'a>,::membuffer::MemBufferError> {{", name).unwrap();
+    writeln!(out, "        reader.check_schema::<{}View>()?;", name).unwrap();
+    for (index,field) in def.fields.iter().enumerate() {
+        //The value type of a map is stored in its payload and not checked by check_schema
+        if let Type::Map(_,value) = &field.field_type {
+            let expected = format!("::membuffer::MemBufferTypes::{} as i32", entry_type(value));
+            writeln!(out, "        if !reader.is_absent({}) {{", index).unwrap();
+            writeln!(out, "            let value_type = reader.load_map({})?.value_type();", index).unwrap();
+            writeln!(out, "            if value_type != {} {{", expected).unwrap();
+            writeln!(out, "                return Err(::membuffer::MemBufferError::FieldTypeError(value_type, {}));", expected).unwrap();
+            writeln!(out, "            }}").unwrap();
+            writeln!(out, "        }}").unwrap();
+        }
+    }
+    writeln!(out, "        Ok({}View {{ reader }})", name).unwrap();
+    writeln!(out, "    }}\n").unwrap();
+    writeln!(out, "    pub fn reader(&self) -> &::membuffer::MemBufferReader<'a> {{").unwrap();
+    writeln!(out, "        &self.reader").unwrap();
+    writeln!(out, "    }}").unwrap();
+    for (index,field) in def.fields.iter().enumerate() {
+        writeln!(out).unwrap();
+        write_doc(out, "    ", &field.doc);
+        let view = view_type(&field.field_type);
+        let load = match &field.field_type {
+            Type::List(element) if referenced_struct(element).is_some() => format!("self.reader.load_buffer_array({}).map({}::from_array)", index, view.trim_end_matches("<'a>")),
+            Type::Map(_,_) => format!("self.reader.load_map({})", index),
+            Type::Struct(_) => format!("self.reader.load_recursive_reader({}).and_then({}::from_reader)", index, view.trim_end_matches("<'a>")),
+            _ => format!("self.reader.load_entry({})", index),
+        };
+        let simple = match &field.field_type {
+            Type::List(element) => referenced_struct(element).is_none(),
+            Type::Map(_,_) | Type::Struct(_) => false,
+            _ => true,
+        };
+        if !field.optional {
+            writeln!(out, "    pub fn {}(&self) -> ::std::result::Result<{},::membuffer::MemBufferError> {{", field.name, view).unwrap();
+            writeln!(out, "        {}", load).unwrap();
+        } else if simple {
+            //Option loads null entries and keys past the end as None on its own
+            writeln!(out, "    pub fn {}(&self) -> ::std::result::Result<Option<{}>,::membuffer::MemBufferError> {{", field.name, view).unwrap();
+            writeln!(out, "        {}", load).unwrap();
+        } else {
+            writeln!(out, "    pub fn {}(&self) -> ::std::result::Result<Option<{}>,::membuffer::MemBufferError> {{", field.name, view).unwrap();
+            writeln!(out, "        if self.reader.is_absent({}) {{", index).unwrap();
+            writeln!(out, "            return Ok(None);").unwrap();
+            writeln!(out, "        }}").unwrap();
+            writeln!(out, "        {}.map(Some)", load).unwrap();
+        }
+        writeln!(out, "    }}").unwrap();
+    }
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(out, "///Zero copy view on a list of buffers with the layout of `{}`", name).unwrap();
+    writeln!(out, "#[derive(Clone, Copy)]").unwrap();
+    writeln!(out, "pub struct {}ListView<'a> {{", name).unwrap();
+    writeln!(out, "    array: ::membuffer::BufferArray<'a>,").unwrap();
+    writeln!(out, "}}\n").unwrap();
+    writeln!(out, "impl<'a> {}ListView<'a> {{", name).unwrap();
+    writeln!(out, "    pub fn from_array(array: ::membuffer::BufferArray<'a>) -> {}ListView<'a> {{", name).unwrap();
+    writeln!(out, "        {}ListView {{ array }}", name).unwrap();
+    writeln!(out, "    }}\n").unwrap();
+    writeln!(out, "    pub fn len(&self) -> usize {{").unwrap();
+    writeln!(out, "        self.array.len()").unwrap();
+    writeln!(out, "    }}\n").unwrap();
+    writeln!(out, "    pub fn is_empty(&self) -> bool {{").unwrap();
+    writeln!(out, "        self.array.is_empty()").unwrap();
+    writeln!(out, "    }}\n").unwrap();
+    writeln!(out, "    ///Opens the buffer with the given index and checks it against the layout").unwrap();
+    writeln!(out, "    pub fn get(&self, index: usize) -> ::std::result::Result<{}View<'a>,::membuffer::MemBufferError> {{", name).unwrap();
+    writeln!(out, "        self.array.get(index).and_then({}View::from_reader)", name).unwrap();
+    writeln!(out, "    }}\n").unwrap();
+    writeln!(out, "    pub fn iter(&self) -> impl Iterator<Item=::std::result::Result<{}View<'a>,::membuffer::MemBufferError>> + '_ {{", name).unwrap();
+    writeln!(out, "        (0..self.len()).map(move |index| self.get(index))").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}\n").unwrap();
+}
+
+///Generates the builders and views for all structs of the schema
+///```rust
+///let code = membuffer_build::generate("struct Point { x: f64; y: f64; }").unwrap();
+///assert!(code.contains("pub struct PointBuilder<'a>"));
+///assert!(code.contains("pub struct PointView<'a>"));
+///```
+pub fn generate(source: &str) -> Result<String,Error> {
+    let structs = parse(source)?;
+    let mut out = String::from("//Generated by membuffer-build, do not edit\n\n");
+    for def in &structs {
+        generate_builder(&mut out, def);
+        generate_view(&mut out, def);
+    }
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    Ok(out)
+}
+
+///Compiles the schema file into `$OUT_DIR/<name of the schema file>.rs` and returns the path of
+///the generated file, call this from `build.rs`. Cargo is told to rerun the build script when the
+///schema file changes.
+pub fn compile<P: AsRef<Path>>(schema: P) -> Result<PathBuf,Error> {
+    let schema = schema.as_ref();
+    let out_dir = std::env::var_os("OUT_DIR")
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "OUT_DIR is not set, compile schemas from a build script"))?;
+    println!("cargo:rerun-if-changed={}", schema.display());
+    let code = generate(&std::fs::read_to_string(schema)?)?;
+    let stem = schema.file_stem()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "the schema path does not name a file"))?;
+    let target = Path::new(&out_dir).join(stem).with_extension("rs");
+    std::fs::write(&target, code)?;
+    Ok(target)
+}
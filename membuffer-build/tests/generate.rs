@@ -0,0 +1,195 @@
+use membuffer::{MemBufferError,MemBufferReader,MemBufferSerialize,MemBufferTypes,MemBufferWriter};
+use membuffer_build::{generate,parse,Error,Type};
+
+//The generated code of the schema is checked in so the tests compile it, run the tests with
+//MEMBUFFER_BLESS=1 to regenerate it after changing the generator
+#[allow(dead_code)]
+mod article {
+    include!("schema/article.rs");
+}
+
+use article::{ArticleBuilder,ArticleView,AuthorBuilder,AuthorView};
+
+#[test]
+fn check_generated_up_to_date() {
+    let code = generate(include_str!("schema/article.mbs")).unwrap();
+    if std::env::var_os("MEMBUFFER_BLESS").is_some() {
+        std::fs::write(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/schema/article.rs"), &code).unwrap();
+    }
+    assert_eq!(code, include_str!("schema/article.rs"));
+}
+
+fn author(name: &str, id: u32) -> AuthorBuilder<'_> {
+    let mut author = AuthorBuilder::new();
+    author.name(name).id(id);
+    author
+}
+
+#[test]
+fn check_generated_roundtrip() {
+    let samples = [0.5f32,1.5];
+    let tags = ["rust","mmap"];
+    let chunks: [&[u8]; 2] = [b"ab",b"c"];
+    let mut article = ArticleBuilder::new();
+    article.title("Membuffer")
+        .views(1000)
+        .rating(4.5)
+        .published(true)
+        .samples(&samples)
+        .tags(&tags)
+        .chunks(&chunks)
+        .thumbnail(&[1,2,3])
+        .author(author("Alexander", 1))
+        .reviewers(vec![author("First", 2), author("Second", 3)])
+        .counts(vec![("likes",10),("shares",2)])
+        .summary("Fast deserialization");
+    let data = article.finalize().unwrap();
+
+    let view = ArticleView::new(&data).unwrap();
+    assert_eq!(view.title().unwrap(), "Membuffer");
+    assert_eq!(view.views().unwrap(), 1000);
+    assert_eq!(view.rating().unwrap(), 4.5);
+    assert!(view.published().unwrap());
+    assert_eq!(&view.samples().unwrap()[..], &samples);
+    let loaded: Vec<&str> = view.tags().unwrap().iter().map(|x| x.unwrap()).collect();
+    assert_eq!(loaded, tags);
+    assert_eq!(view.chunks().unwrap().get(1).unwrap(), b"c");
+    assert_eq!(view.thumbnail().unwrap(), &[1,2,3]);
+    assert_eq!(view.author().unwrap().name().unwrap(), "Alexander");
+    assert!(view.editor().unwrap().is_none());
+    let reviewers = view.reviewers().unwrap();
+    assert_eq!(reviewers.len(), 2);
+    let ids: Vec<u32> = reviewers.iter().map(|x| x.unwrap().id().unwrap()).collect();
+    assert_eq!(ids, vec![2,3]);
+    assert_eq!(view.counts().unwrap().get_as::<u64,_>("shares").unwrap(), Some(2));
+    assert_eq!(view.summary().unwrap(), Some("Fast deserialization"));
+    assert!(view.ranks().unwrap().is_none());
+    assert_eq!(view.reader().len(), 14);
+}
+
+#[test]
+fn check_generated_optional_fields() {
+    let mut article = ArticleBuilder::new();
+    article.title("Membuffer")
+        .views(1)
+        .rating(1.0)
+        .published(false)
+        .samples(&[])
+        .tags(&[])
+        .chunks(&[])
+        .thumbnail(&[])
+        .author(author("Alexander", 1))
+        .editor(author("Editor", 2))
+        .reviewers(Vec::new())
+        .counts(Vec::new())
+        .ranks(&[3,-4]);
+    let data = article.finalize().unwrap();
+    let view = ArticleView::new(&data).unwrap();
+    assert_eq!(view.editor().unwrap().unwrap().name().unwrap(), "Editor");
+    assert!(view.summary().unwrap().is_none());
+    assert_eq!(&view.ranks().unwrap().unwrap()[..], &[3,-4]);
+    assert!(view.reviewers().unwrap().is_empty());
+
+    //Required fields have to be set
+    let mut article = ArticleBuilder::new();
+    article.title("Membuffer");
+    assert!(matches!(article.finalize(), Err(MemBufferError::MissingField { name: "views" })));
+}
+
+#[test]
+fn check_generated_layout_check() {
+    let data = author("Alexander", 1).finalize().unwrap();
+    assert_eq!(AuthorView::new(&data).unwrap().id().unwrap(), 1);
+    //The header of the author does not match the layout of an article
+    assert!(matches!(ArticleView::new(&data), Err(MemBufferError::FieldTypeError(_,_))));
+
+    let mut writer = MemBufferWriter::new();
+    writer.add_entry("Alexander");
+    let data = writer.finalize().unwrap();
+    assert!(matches!(AuthorView::from_reader(MemBufferReader::new(&data).unwrap()), Err(MemBufferError::KeyOutOfRange { key: 1, len: 1 })));
+}
+
+//Writes an article by hand with the given value in the map of counts
+fn article_with_count<V: MemBufferSerialize>(count: V) -> Vec<u8> {
+    let mut writer = MemBufferWriter::new();
+    writer.add_entry("Membuffer");
+    writer.add_entry(1000u64);
+    writer.add_entry(4.5f64);
+    writer.add_entry(true);
+    writer.add_entry::<&[f32]>(&[0.5]);
+    writer.add_entry::<&[&str]>(&["rust"]);
+    writer.add_entry::<&[&[u8]]>(&[b"ab"]);
+    writer.add_entry::<&[u8]>(&[1,2,3]);
+    writer.add_entry(author("Alexander", 1).to_mem_buffer_writer().unwrap());
+    writer.add_entry(None::<&str>);
+    writer.add_buffer_array(Vec::<MemBufferWriter>::new());
+    writer.add_map(vec![("likes",count)]).unwrap();
+    writer.finalize().unwrap()
+}
+
+#[test]
+fn check_generated_map_value_check() {
+    let data = article_with_count(10u64);
+    assert_eq!(ArticleView::new(&data).unwrap().counts().unwrap().get_as::<u64,_>("likes").unwrap(), Some(10));
+
+    let data = article_with_count(10i32);
+    match ArticleView::new(&data) {
+        Err(MemBufferError::FieldTypeError(found,expected)) => {
+            assert_eq!(found, MemBufferTypes::Integer32 as i32);
+            assert_eq!(expected, MemBufferTypes::UInteger64 as i32);
+        }
+        other => panic!("expected a type error, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn check_parse() {
+    let structs = parse("
+        ///A point
+        struct Point {
+            //Not a doc comment
+            x: f64;
+            ids: [u64]?;
+            raw: [u8];
+            names: map<bytes, string>;
+        }
+    ").unwrap();
+    assert_eq!(structs.len(), 1);
+    assert_eq!(structs[0].doc, vec!["A point"]);
+    let fields = &structs[0].fields;
+    assert!(fields[0].doc.is_empty());
+    assert_eq!(fields[0].field_type, Type::Scalar("f64"));
+    assert_eq!(fields[1].field_type, Type::List(Box::new(Type::Scalar("u64"))));
+    assert!(fields[1].optional);
+    assert_eq!(fields[2].field_type, Type::Bytes);
+    assert_eq!(fields[3].field_type, Type::Map(Box::new(Type::Bytes),Box::new(Type::String)));
+}
+
+fn parse_error_line(source: &str) -> usize {
+    match parse(source) {
+        Err(Error::Parse { line, .. }) => line,
+        other => panic!("expected a parse error, got {:?}", other),
+    }
+}
+
+#[test]
+fn check_parse_errors() {
+    assert_eq!(parse_error_line("struct A {\n  x: Unknown;\n}"), 2);
+    assert_eq!(parse_error_line("struct A {\n  x: u32\n}"), 3);
+    assert_eq!(parse_error_line("struct A {\n  x: u32;\n  x: u64;\n}"), 3);
+    assert_eq!(parse_error_line("struct A {}\nstruct A {}"), 2);
+    assert_eq!(parse_error_line("struct A {\n  type: u32;\n}"), 2);
+    assert_eq!(parse_error_line("struct A {\n  reader: u32;\n}"), 2);
+    assert_eq!(parse_error_line("struct A {\n  Upper: u32;\n}"), 2);
+    assert_eq!(parse_error_line("struct a {}"), 1);
+    assert_eq!(parse_error_line("struct A {\n  x: [bool];\n}"), 2);
+    assert_eq!(parse_error_line("struct A {\n  x: [[u32]];\n}"), 2);
+    assert_eq!(parse_error_line("struct A {\n  x: map<u32, u32>;\n}"), 2);
+    assert_eq!(parse_error_line("struct A {\n  x: map<string, A>;\n}"), 2);
+    assert_eq!(parse_error_line("struct A {\n  x: u32 = 1;\n}"), 2);
+    assert_eq!(parse_error_line("enum A {}"), 1);
+    assert_eq!(parse_error_line("struct A {\n  x: u32;"), 2);
+    assert_eq!(parse_error_line("struct Foo {}\n\nstruct FooList {}"), 3);
+    assert_eq!(parse_error_line("struct FooList {}\nstruct Foo {}"), 2);
+    assert!(generate("").unwrap().starts_with("//Generated"));
+}
@@ -0,0 +1,426 @@
+//Generated by membuffer-build, do not edit
+
+///An author of articles
+///
+///Builder writing buffers with the layout of `Author`, see `AuthorView`
+#[derive(Default)]
+pub struct AuthorBuilder<'a> {
+    name: Option<&'a str>,
+    id: Option<u32>,
+    _lifetime: ::std::marker::PhantomData<&'a ()>,
+}
+
+impl<'a> AuthorBuilder<'a> {
+    pub fn new() -> AuthorBuilder<'a> {
+        Default::default()
+    }
+
+    ///Full name of the author
+    pub fn name(&mut self, value: &'a str) -> &mut Self {
+        self.name = Some(value);
+        self
+    }
+
+    pub fn id(&mut self, value: u32) -> &mut Self {
+        self.id = Some(value);
+        self
+    }
+
+    ///Writes every field as one entry in the order of declaration, fails with `MissingField` if
+    ///a required field was not set
+    pub fn to_mem_buffer_writer(&self) -> ::std::result::Result<::membuffer::MemBufferWriter,::membuffer::MemBufferError> {
+        let mut writer = ::membuffer::MemBufferWriter::new();
+        match &self.name {
+            Some(value) => writer.add_entry(*value),
+            None => return Err(::membuffer::MemBufferError::MissingField { name: "name" }),
+        }
+        match &self.id {
+            Some(value) => writer.add_entry(*value),
+            None => return Err(::membuffer::MemBufferError::MissingField { name: "id" }),
+        }
+        Ok(writer)
+    }
+
+    pub fn finalize(&self) -> ::std::result::Result<Vec<u8>,::membuffer::MemBufferError> {
+        self.to_mem_buffer_writer()?.finalize()
+    }
+}
+
+///An author of articles
+///
+///Zero copy view on a buffer with the layout of `Author`, the header is checked against the
+///layout once when the view is created
+#[derive(Debug)]
+pub struct AuthorView<'a> {
+    reader: ::membuffer::MemBufferReader<'a>,
+}
+
+impl ::membuffer::Schema for AuthorView<'_> {
+    fn fields() -> Vec<::membuffer::FieldInfo> {
+        vec![
+            ::membuffer::FieldInfo { index: 0, type_id: ::membuffer::MemBufferTypes::Text as i32, nullable: false, optional: false },
+            ::membuffer::FieldInfo { index: 1, type_id: ::membuffer::MemBufferTypes::UInteger32 as i32, nullable: false, optional: false },
+        ]
+    }
+}
+
+impl<'a> AuthorView<'a> {
+    ///Opens the buffer and checks the types of all entries against the layout
+    pub fn new(data: &'a [u8]) -> ::std::result::Result<AuthorView<'a>,::membuffer::MemBufferError> {
+        AuthorView::from_reader(::membuffer::MemBufferReader::new(data)?)
+    }
+
+    ///Checks the types of all entries of the reader and the value types of maps against the layout
+    pub fn from_reader(reader: ::membuffer::MemBufferReader<'a>) -> ::std::result::Result<AuthorView<'a>,::membuffer::MemBufferError> {
+        reader.check_schema::<AuthorView>()?;
+        Ok(AuthorView { reader })
+    }
+
+    pub fn reader(&self) -> &::membuffer::MemBufferReader<'a> {
+        &self.reader
+    }
+
+    ///Full name of the author
+    pub fn name(&self) -> ::std::result::Result<&'a str,::membuffer::MemBufferError> {
+        self.reader.load_entry(0)
+    }
+
+    pub fn id(&self) -> ::std::result::Result<u32,::membuffer::MemBufferError> {
+        self.reader.load_entry(1)
+    }
+}
+
+///Zero copy view on a list of buffers with the layout of `Author`
+#[derive(Clone, Copy)]
+pub struct AuthorListView<'a> {
+    array: ::membuffer::BufferArray<'a>,
+}
+
+impl<'a> AuthorListView<'a> {
+    pub fn from_array(array: ::membuffer::BufferArray<'a>) -> AuthorListView<'a> {
+        AuthorListView { array }
+    }
+
+    pub fn len(&self) -> usize {
+        self.array.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.array.is_empty()
+    }
+
+    ///Opens the buffer with the given index and checks it against the layout
+    pub fn get(&self, index: usize) -> ::std::result::Result<AuthorView<'a>,::membuffer::MemBufferError> {
+        self.array.get(index).and_then(AuthorView::from_reader)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=::std::result::Result<AuthorView<'a>,::membuffer::MemBufferError>> + '_ {
+        (0..self.len()).map(move |index| self.get(index))
+    }
+}
+
+///An article with every kind of field
+///
+///Builder writing buffers with the layout of `Article`, see `ArticleView`
+#[derive(Default)]
+pub struct ArticleBuilder<'a> {
+    title: Option<&'a str>,
+    views: Option<u64>,
+    rating: Option<f64>,
+    published: Option<bool>,
+    samples: Option<&'a [f32]>,
+    tags: Option<&'a [&'a str]>,
+    chunks: Option<&'a [&'a [u8]]>,
+    thumbnail: Option<&'a [u8]>,
+    author: Option<Box<AuthorBuilder<'a>>>,
+    editor: Option<Box<AuthorBuilder<'a>>>,
+    reviewers: Option<Vec<AuthorBuilder<'a>>>,
+    counts: Option<Vec<(&'a str, u64)>>,
+    summary: Option<&'a str>,
+    ranks: Option<&'a [i32]>,
+    _lifetime: ::std::marker::PhantomData<&'a ()>,
+}
+
+impl<'a> ArticleBuilder<'a> {
+    pub fn new() -> ArticleBuilder<'a> {
+        Default::default()
+    }
+
+    pub fn title(&mut self, value: &'a str) -> &mut Self {
+        self.title = Some(value);
+        self
+    }
+
+    pub fn views(&mut self, value: u64) -> &mut Self {
+        self.views = Some(value);
+        self
+    }
+
+    pub fn rating(&mut self, value: f64) -> &mut Self {
+        self.rating = Some(value);
+        self
+    }
+
+    pub fn published(&mut self, value: bool) -> &mut Self {
+        self.published = Some(value);
+        self
+    }
+
+    pub fn samples(&mut self, value: &'a [f32]) -> &mut Self {
+        self.samples = Some(value);
+        self
+    }
+
+    pub fn tags(&mut self, value: &'a [&'a str]) -> &mut Self {
+        self.tags = Some(value);
+        self
+    }
+
+    pub fn chunks(&mut self, value: &'a [&'a [u8]]) -> &mut Self {
+        self.chunks = Some(value);
+        self
+    }
+
+    pub fn thumbnail(&mut self, value: &'a [u8]) -> &mut Self {
+        self.thumbnail = Some(value);
+        self
+    }
+
+    pub fn author(&mut self, value: AuthorBuilder<'a>) -> &mut Self {
+        self.author = Some(Box::new(value));
+        self
+    }
+
+    pub fn editor(&mut self, value: AuthorBuilder<'a>) -> &mut Self {
+        self.editor = Some(Box::new(value));
+        self
+    }
+
+    pub fn reviewers<I: IntoIterator<Item=AuthorBuilder<'a>>>(&mut self, values: I) -> &mut Self {
+        self.reviewers = Some(values.into_iter().collect());
+        self
+    }
+
+    pub fn counts<I: IntoIterator<Item=(&'a str, u64)>>(&mut self, pairs: I) -> &mut Self {
+        self.counts = Some(pairs.into_iter().collect());
+        self
+    }
+
+    pub fn summary(&mut self, value: &'a str) -> &mut Self {
+        self.summary = Some(value);
+        self
+    }
+
+    pub fn ranks(&mut self, value: &'a [i32]) -> &mut Self {
+        self.ranks = Some(value);
+        self
+    }
+
+    ///Writes every field as one entry in the order of declaration, fails with `MissingField` if
+    ///a required field was not set
+    pub fn to_mem_buffer_writer(&self) -> ::std::result::Result<::membuffer::MemBufferWriter,::membuffer::MemBufferError> {
+        let mut writer = ::membuffer::MemBufferWriter::new();
+        match &self.title {
+            Some(value) => writer.add_entry(*value),
+            None => return Err(::membuffer::MemBufferError::MissingField { name: "title" }),
+        }
+        match &self.views {
+            Some(value) => writer.add_entry(*value),
+            None => return Err(::membuffer::MemBufferError::MissingField { name: "views" }),
+        }
+        match &self.rating {
+            Some(value) => writer.add_entry(*value),
+            None => return Err(::membuffer::MemBufferError::MissingField { name: "rating" }),
+        }
+        match &self.published {
+            Some(value) => writer.add_entry(*value),
+            None => return Err(::membuffer::MemBufferError::MissingField { name: "published" }),
+        }
+        match &self.samples {
+            Some(value) => writer.add_entry(*value),
+            None => return Err(::membuffer::MemBufferError::MissingField { name: "samples" }),
+        }
+        match &self.tags {
+            Some(value) => writer.add_entry(*value),
+            None => return Err(::membuffer::MemBufferError::MissingField { name: "tags" }),
+        }
+        match &self.chunks {
+            Some(value) => writer.add_entry(*value),
+            None => return Err(::membuffer::MemBufferError::MissingField { name: "chunks" }),
+        }
+        match &self.thumbnail {
+            Some(value) => writer.add_entry(*value),
+            None => return Err(::membuffer::MemBufferError::MissingField { name: "thumbnail" }),
+        }
+        match &self.author {
+            Some(value) => writer.add_entry(value.to_mem_buffer_writer()?),
+            None => return Err(::membuffer::MemBufferError::MissingField { name: "author" }),
+        }
+        match &self.editor {
+            Some(value) => writer.add_entry(value.to_mem_buffer_writer()?),
+            None => writer.add_entry(None::<&str>),
+        }
+        match &self.reviewers {
+            Some(value) => writer.add_buffer_array(value.iter().map(|x| x.to_mem_buffer_writer()).collect::<::std::result::Result<Vec<_>,_>>()?),
+            None => return Err(::membuffer::MemBufferError::MissingField { name: "reviewers" }),
+        }
+        match &self.counts {
+            Some(value) => writer.add_map(value.iter().copied())?,
+            None => return Err(::membuffer::MemBufferError::MissingField { name: "counts" }),
+        }
+        match &self.summary {
+            Some(value) => writer.add_entry(*value),
+            None => writer.add_entry(None::<&str>),
+        }
+        match &self.ranks {
+            Some(value) => writer.add_entry(*value),
+            None => writer.add_entry(None::<&str>),
+        }
+        Ok(writer)
+    }
+
+    pub fn finalize(&self) -> ::std::result::Result<Vec<u8>,::membuffer::MemBufferError> {
+        self.to_mem_buffer_writer()?.finalize()
+    }
+}
+
+///An article with every kind of field
+///
+///Zero copy view on a buffer with the layout of `Article`, the header is checked against the
+///layout once when the view is created
+#[derive(Debug)]
+pub struct ArticleView<'a> {
+    reader: ::membuffer::MemBufferReader<'a>,
+}
+
+impl ::membuffer::Schema for ArticleView<'_> {
+    fn fields() -> Vec<::membuffer::FieldInfo> {
+        vec![
+            ::membuffer::FieldInfo { index: 0, type_id: ::membuffer::MemBufferTypes::Text as i32, nullable: false, optional: false },
+            ::membuffer::FieldInfo { index: 1, type_id: ::membuffer::MemBufferTypes::UInteger64 as i32, nullable: false, optional: false },
+            ::membuffer::FieldInfo { index: 2, type_id: ::membuffer::MemBufferTypes::Float64 as i32, nullable: false, optional: false },
+            ::membuffer::FieldInfo { index: 3, type_id: ::membuffer::MemBufferTypes::Bool as i32, nullable: false, optional: false },
+            ::membuffer::FieldInfo { index: 4, type_id: ::membuffer::MemBufferTypes::VectorF32 as i32, nullable: false, optional: false },
+            ::membuffer::FieldInfo { index: 5, type_id: ::membuffer::MemBufferTypes::TextList as i32, nullable: false, optional: false },
+            ::membuffer::FieldInfo { index: 6, type_id: ::membuffer::MemBufferTypes::BytesList as i32, nullable: false, optional: false },
+            ::membuffer::FieldInfo { index: 7, type_id: ::membuffer::MemBufferTypes::VectorU8 as i32, nullable: false, optional: false },
+            ::membuffer::FieldInfo { index: 8, type_id: ::membuffer::MemBufferTypes::MemBuffer as i32, nullable: false, optional: false },
+            ::membuffer::FieldInfo { index: 9, type_id: ::membuffer::MemBufferTypes::MemBuffer as i32, nullable: true, optional: true },
+            ::membuffer::FieldInfo { index: 10, type_id: ::membuffer::MemBufferTypes::BufferArray as i32, nullable: false, optional: false },
+            ::membuffer::FieldInfo { index: 11, type_id: ::membuffer::MemBufferTypes::HashMap as i32, nullable: false, optional: false },
+            ::membuffer::FieldInfo { index: 12, type_id: ::membuffer::MemBufferTypes::Text as i32, nullable: true, optional: true },
+            ::membuffer::FieldInfo { index: 13, type_id: ::membuffer::MemBufferTypes::VectorI32 as i32, nullable: true, optional: true },
+        ]
+    }
+}
+
+impl<'a> ArticleView<'a> {
+    ///Opens the buffer and checks the types of all entries against the layout
+    pub fn new(data: &'a [u8]) -> ::std::result::Result<ArticleView<'a>,::membuffer::MemBufferError> {
+        ArticleView::from_reader(::membuffer::MemBufferReader::new(data)?)
+    }
+
+    ///Checks the types of all entries of the reader and the value types of maps against the layout
+    pub fn from_reader(reader: ::membuffer::MemBufferReader<'a>) -> ::std::result::Result<ArticleView<'a>,::membuffer::MemBufferError> {
+        reader.check_schema::<ArticleView>()?;
+        if !reader.is_absent(11) {
+            let value_type = reader.load_map(11)?.value_type();
+            if value_type != ::membuffer::MemBufferTypes::UInteger64 as i32 {
+                return Err(::membuffer::MemBufferError::FieldTypeError(value_type, ::membuffer::MemBufferTypes::UInteger64 as i32));
+            }
+        }
+        Ok(ArticleView { reader })
+    }
+
+    pub fn reader(&self) -> &::membuffer::MemBufferReader<'a> {
+        &self.reader
+    }
+
+    pub fn title(&self) -> ::std::result::Result<&'a str,::membuffer::MemBufferError> {
+        self.reader.load_entry(0)
+    }
+
+    pub fn views(&self) -> ::std::result::Result<u64,::membuffer::MemBufferError> {
+        self.reader.load_entry(1)
+    }
+
+    pub fn rating(&self) -> ::std::result::Result<f64,::membuffer::MemBufferError> {
+        self.reader.load_entry(2)
+    }
+
+    pub fn published(&self) -> ::std::result::Result<bool,::membuffer::MemBufferError> {
+        self.reader.load_entry(3)
+    }
+
+    pub fn samples(&self) -> ::std::result::Result<::std::borrow::Cow<'a,[f32]>,::membuffer::MemBufferError> {
+        self.reader.load_entry(4)
+    }
+
+    pub fn tags(&self) -> ::std::result::Result<::membuffer::StrList<'a>,::membuffer::MemBufferError> {
+        self.reader.load_entry(5)
+    }
+
+    pub fn chunks(&self) -> ::std::result::Result<::membuffer::BytesList<'a>,::membuffer::MemBufferError> {
+        self.reader.load_entry(6)
+    }
+
+    pub fn thumbnail(&self) -> ::std::result::Result<&'a [u8],::membuffer::MemBufferError> {
+        self.reader.load_entry(7)
+    }
+
+    pub fn author(&self) -> ::std::result::Result<AuthorView<'a>,::membuffer::MemBufferError> {
+        self.reader.load_recursive_reader(8).and_then(AuthorView::from_reader)
+    }
+
+    pub fn editor(&self) -> ::std::result::Result<Option<AuthorView<'a>>,::membuffer::MemBufferError> {
+        if self.reader.is_absent(9) {
+            return Ok(None);
+        }
+        self.reader.load_recursive_reader(9).and_then(AuthorView::from_reader).map(Some)
+    }
+
+    pub fn reviewers(&self) -> ::std::result::Result<AuthorListView<'a>,::membuffer::MemBufferError> {
+        self.reader.load_buffer_array(10).map(AuthorListView::from_array)
+    }
+
+    pub fn counts(&self) -> ::std::result::Result<::membuffer::MemBufferMap<'a>,::membuffer::MemBufferError> {
+        self.reader.load_map(11)
+    }
+
+    pub fn summary(&self) -> ::std::result::Result<Option<&'a str>,::membuffer::MemBufferError> {
+        self.reader.load_entry(12)
+    }
+
+    pub fn ranks(&self) -> ::std::result::Result<Option<::std::borrow::Cow<'a,[i32]>>,::membuffer::MemBufferError> {
+        self.reader.load_entry(13)
+    }
+}
+
+///Zero copy view on a list of buffers with the layout of `Article`
+#[derive(Clone, Copy)]
+pub struct ArticleListView<'a> {
+    array: ::membuffer::BufferArray<'a>,
+}
+
+impl<'a> ArticleListView<'a> {
+    pub fn from_array(array: ::membuffer::BufferArray<'a>) -> ArticleListView<'a> {
+        ArticleListView { array }
+    }
+
+    pub fn len(&self) -> usize {
+        self.array.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.array.is_empty()
+    }
+
+    ///Opens the buffer with the given index and checks it against the layout
+    pub fn get(&self, index: usize) -> ::std::result::Result<ArticleView<'a>,::membuffer::MemBufferError> {
+        self.array.get(index).and_then(ArticleView::from_reader)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=::std::result::Result<ArticleView<'a>,::membuffer::MemBufferError>> + '_ {
+        (0..self.len()).map(move |index| self.get(index))
+    }
+}
@@ -0,0 +1,183 @@
+//!Derive macro for the membuffer crate. Deriving `MemBuffer` for a struct with named fields
+//!generates `to_mem_buffer_writer` which writes every field as one entry in the order of
+//!declaration and a view type named after the struct with a `View` suffix which wraps a
+//!`MemBufferReader` and loads a field only when its accessor is called:
+//!```rust
+//!use membuffer_derive::MemBuffer;
+//!
+//!#[derive(MemBuffer)]
+//!struct Article {
+//!    title: String,
+//!    views: u64,
+//!    tags: Option<Vec<String>>,
+//!}
+//!
+//!let article = Article { title: String::from("Membuffer"), views: 10, tags: None };
+//!let data = article.to_mem_buffer_writer().unwrap().finalize().unwrap();
+//!
+//!let view = ArticleView::new(&data).unwrap();
+//!assert_eq!(view.title().unwrap(), "Membuffer");
+//!assert_eq!(view.views().unwrap(), 10);
+//!assert!(view.tags().unwrap().is_none());
+//!```
+//!Fields are stored and loaded through `membuffer::MemBufferView`, the accessor of a field
+//!returns the view type of the field, e. g. `&str` for a `String`. Fields of structs which
+//!derive `MemBuffer` themselves are stored as nested buffers and loaded as their view. Fields
+//!marked with `#[membuffer(serde)]` are stored as serde entry encoded with bincode and fields
+//!marked with `#[membuffer(skip)]` are not stored at all.
+//!
+//!Stored fields cannot be named like the methods of the view, `new`, `from_reader` and
+//!`reader`, as their accessors would clash with them:
+//!```compile_fail
+//!use membuffer_derive::MemBuffer;
+//!
+//!#[derive(MemBuffer)]
+//!struct Connection {
+//!    reader: String,
+//!}
+//!```
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields};
+
+///Methods of the generated view which cannot be used as accessor of a field
+const RESERVED_NAMES: &[&str] = &["new", "from_reader", "reader"];
+
+///How a field of the struct is stored
+enum FieldKind {
+    View,
+    Serde,
+    Skip,
+}
+
+///Reads the membuffer attributes of the field
+fn field_kind(field: &syn::Field) -> syn::Result<FieldKind> {
+    let mut kind = FieldKind::View;
+    for attr in field.attrs.iter().filter(|x| x.path().is_ident("membuffer")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                kind = FieldKind::Skip;
+            } else if meta.path.is_ident("serde") {
+                kind = FieldKind::Serde;
+            } else {
+                return Err(meta.error("unknown membuffer attribute, expected `skip` or `serde`"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(kind)
+}
+
+#[proc_macro_derive(MemBuffer, attributes(membuffer))]
+pub fn derive_mem_buffer(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input).unwrap_or_else(|err| err.to_compile_error()).into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(&input.generics, "MemBuffer cannot be derived for generic structs"));
+    }
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new_spanned(&input.ident, "MemBuffer can only be derived for structs with named fields")),
+        },
+        _ => return Err(Error::new_spanned(&input.ident, "MemBuffer can only be derived for structs")),
+    };
+
+    let name = &input.ident;
+    let vis = &input.vis;
+    let view = format_ident!("{}View", name);
+    let mut writes = Vec::new();
+    let mut accessors = Vec::new();
+    let mut key = 0usize;
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named fields have an identifier");
+        let ty = &field.ty;
+        let field_vis = &field.vis;
+        let doc = format!("Loads the field `{}` stored at key {}", ident, key);
+        let kind = field_kind(field)?;
+        if !matches!(kind, FieldKind::Skip) && RESERVED_NAMES.iter().any(|x| ident == x) {
+            return Err(Error::new_spanned(ident, format!("the field `{}` clashes with the method of the same name of `{}`, rename it or mark it with #[membuffer(skip)]", ident, view)));
+        }
+        match kind {
+            FieldKind::Skip => continue,
+            FieldKind::View => {
+                writes.push(quote! {
+                    ::membuffer::MemBufferView::add_to(&self.#ident, &mut writer)?;
+                });
+                accessors.push(quote! {
+                    #[doc = #doc]
+                    #field_vis fn #ident(&self) -> ::std::result::Result<<#ty as ::membuffer::MemBufferView<'a>>::View,::membuffer::MemBufferError> {
+                        <#ty as ::membuffer::MemBufferView<'a>>::load_from(&self.reader, #key)
+                    }
+                });
+            }
+            FieldKind::Serde => {
+                writes.push(quote! {
+                    writer.add_serde_entry(&self.#ident)?;
+                });
+                accessors.push(quote! {
+                    #[doc = #doc]
+                    #field_vis fn #ident(&self) -> ::std::result::Result<#ty,::membuffer::MemBufferError> {
+                        self.reader.load_serde_entry(#key)
+                    }
+                });
+            }
+        }
+        key += 1;
+    }
+
+    let view_doc = format!("Lazily loading view on a buffer written by `{}::to_mem_buffer_writer`", name);
+    Ok(quote! {
+        impl #name {
+            ///Writes every field which is not skipped as one entry in the order of declaration
+            #vis fn to_mem_buffer_writer(&self) -> ::std::result::Result<::membuffer::MemBufferWriter,::membuffer::MemBufferError> {
+                let mut writer = ::membuffer::MemBufferWriter::new();
+                #(#writes)*
+                Ok(writer)
+            }
+        }
+
+        impl<'a> ::membuffer::MemBufferView<'a> for #name {
+            type View = #view<'a>;
+
+            fn add_to(&self, writer: &mut ::membuffer::MemBufferWriter) -> ::std::result::Result<(),::membuffer::MemBufferError> {
+                writer.add_entry(self.to_mem_buffer_writer()?);
+                Ok(())
+            }
+
+            fn load_from(reader: &::membuffer::MemBufferReader<'a>, key: usize) -> ::std::result::Result<#view<'a>,::membuffer::MemBufferError> {
+                reader.load_recursive_reader(key).map(#view::from_reader)
+            }
+        }
+
+        #[doc = #view_doc]
+        #[derive(Debug)]
+        #vis struct #view<'a> {
+            reader: ::membuffer::MemBufferReader<'a>,
+        }
+
+        #[allow(dead_code)]
+        impl<'a> #view<'a> {
+            ///Opens the buffer and validates its header, fields are loaded by their accessors
+            #vis fn new(data: &'a [u8]) -> ::std::result::Result<#view<'a>,::membuffer::MemBufferError> {
+                ::membuffer::MemBufferReader::new(data).map(#view::from_reader)
+            }
+
+            #vis fn from_reader(reader: ::membuffer::MemBufferReader<'a>) -> #view<'a> {
+                #view { reader }
+            }
+
+            ///The underlying reader of the buffer
+            #vis fn reader(&self) -> &::membuffer::MemBufferReader<'a> {
+                &self.reader
+            }
+
+            #(#accessors)*
+        }
+    })
+}
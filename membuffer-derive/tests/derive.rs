@@ -0,0 +1,107 @@
+use membuffer::{MemBufferError,MemBufferReader};
+use membuffer_derive::MemBuffer;
+use serde::{Serialize,Deserialize};
+
+#[derive(Serialize,Deserialize,Debug,PartialEq)]
+struct Meta {
+    source: String,
+    score: f64,
+}
+
+#[derive(MemBuffer)]
+struct Author {
+    name: String,
+    id: u32,
+}
+
+#[derive(MemBuffer)]
+struct Article {
+    title: String,
+    views: u64,
+    samples: Vec<f32>,
+    tags: Vec<String>,
+    author: Author,
+    editor: Option<Author>,
+    summary: Option<String>,
+    #[membuffer(serde)]
+    meta: Meta,
+    #[membuffer(skip)]
+    #[allow(dead_code)]
+    cache: Vec<u8>,
+    raw: Vec<u8>,
+}
+
+fn article() -> Article {
+    Article {
+        title: String::from("Membuffer"),
+        views: 1000,
+        samples: vec![0.5,1.5],
+        tags: vec![String::from("rust"),String::from("mmap")],
+        author: Author { name: String::from("Alexander"), id: 1 },
+        editor: None,
+        summary: Some(String::from("Fast deserialization")),
+        meta: Meta { source: String::from("feed"), score: 0.75 },
+        cache: vec![1,2,3],
+        raw: vec![4,5],
+    }
+}
+
+#[test]
+fn check_derived_view() {
+    let data = article().to_mem_buffer_writer().unwrap().finalize().unwrap();
+    let view = ArticleView::new(&data).unwrap();
+    assert_eq!(view.title().unwrap(), "Membuffer");
+    assert_eq!(view.views().unwrap(), 1000);
+    assert_eq!(&view.samples().unwrap()[..], &[0.5,1.5]);
+    let tags: Vec<&str> = view.tags().unwrap().iter().map(|x| x.unwrap()).collect();
+    assert_eq!(tags, vec!["rust","mmap"]);
+    assert_eq!(view.author().unwrap().name().unwrap(), "Alexander");
+    assert_eq!(view.author().unwrap().id().unwrap(), 1);
+    assert!(view.editor().unwrap().is_none());
+    assert_eq!(view.summary().unwrap(), Some("Fast deserialization"));
+    assert_eq!(view.meta().unwrap(), Meta { source: String::from("feed"), score: 0.75 });
+    assert_eq!(view.raw().unwrap(), &[4,5]);
+    //Skipped fields take no entry
+    assert_eq!(view.reader().len(), 9);
+}
+
+#[test]
+fn check_derived_nested_option() {
+    let mut value = article();
+    value.editor = Some(Author { name: String::from("Editor"), id: 2 });
+    value.summary = None;
+    let data = value.to_mem_buffer_writer().unwrap().finalize().unwrap();
+    let view = ArticleView::from_reader(MemBufferReader::new(&data).unwrap());
+    assert_eq!(view.editor().unwrap().unwrap().name().unwrap(), "Editor");
+    assert!(view.summary().unwrap().is_none());
+}
+
+#[test]
+fn check_derived_type_mismatch() {
+    let author = Author { name: String::from("Alexander"), id: 1 };
+    let data = author.to_mem_buffer_writer().unwrap().finalize().unwrap();
+    //The layout of the author does not match the one of an article
+    let view = ArticleView::new(&data).unwrap();
+    assert_eq!(view.title().unwrap(), "Alexander");
+    assert!(matches!(view.views(), Err(MemBufferError::FieldTypeError(_,_))));
+    assert!(matches!(view.samples(), Err(MemBufferError::KeyOutOfRange { key: 2, len: 2 })));
+}
+
+//Only read in the tests, never written
+#[allow(dead_code)]
+#[derive(MemBuffer)]
+struct AuthorV2 {
+    name: String,
+    id: u32,
+    email: Option<String>,
+}
+
+#[test]
+fn check_derived_added_field() {
+    let author = Author { name: String::from("Alexander"), id: 1 };
+    let data = author.to_mem_buffer_writer().unwrap().finalize().unwrap();
+    //Option fields added at the end are loaded as None from older buffers
+    let view = AuthorV2View::new(&data).unwrap();
+    assert_eq!(view.id().unwrap(), 1);
+    assert!(view.email().unwrap().is_none());
+}
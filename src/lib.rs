@@ -1,52 +1,61 @@
+//!This crate will provide a extremely fast deserialization of dynamic data structures with big
+//!fields. This is very MMAP friendly since it only parses the header and does not parse the fields
+//!until requested.
+//!**Easy example:**
+//!```rust
+//!use membuffer::{MemBufferWriter,MemBufferReader};
+//!
+//!fn main() {
+//!  //Creates a new empty MemBufferWriter
+//!  let mut writer = MemBufferWriter::new();
+//!  
+//!  //Adds this as immutable field, no more changing after adding it
+//!  //Entries are stored under their index in the order they were added, starting at 0
+//!  writer.add_entry("Very long value");
+//!
+//!  //Entries can be given a name as well to look them up independent of their index
+//!  writer.add_named_entry("title", "Membuffer").unwrap();
+//!
+//!  //Creates a Vec<u8> out of all the collected data
+//!  let result = writer.finalize().unwrap();
+//!
+//!  //Try to read the created vector. Will return an error if the CRC32 of the header does not
+//!  //fit, if the header is not terminated or if any entry points outside of the payload
+//!  let reader = MemBufferReader::new(&result).unwrap();
+//!
+//!  //Will return an error if the selected key could not be found, if the value types dont match
+//!  //or if the CRC32 of the entry does not fit
+//!  assert_eq!(reader.load_entry::<&str>(0).unwrap(), "Very long value");
+//!  assert_eq!(reader.load_named_entry::<&str>("title").unwrap(), "Membuffer");
+//!}
+//!```
 #![cfg_attr(feature = "bench", feature(test))]
 
 #[cfg(feature = "bench")]
 extern crate test;
 
 
-use byteorder::{WriteBytesExt, ReadBytesExt, NativeEndian,ByteOrder};
+use byteorder::{WriteBytesExt, ReadBytesExt, LittleEndian,NativeEndian,ByteOrder};
 use serde::{Serialize,Deserialize};
-use bincode;
-use std::borrow::Cow;
+use std::borrow::{Borrow,Cow};
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use std::sync::Arc;
 
+#[cfg(feature = "derive")]
+pub use membuffer_derive::MemBuffer;
 
-///!This crate will provide a extremely fast deserialization of dynamic data structures with big
-///fields. This is very MMAP friendly since it only parses the header and does not parse the fields
-///until requested.
-///**Easy example:**
-///```rust
-///use membuffer::{MemBufferWriter,MemBufferReader};
-///
-///fn main() {
-///  //Creates a new empty MemBufferWriter
-///  let mut writer = MemBufferWriter::new();
-///  
-///  //Adds this as immutable field, no more changing after adding it
-///  //The first entry is the key and must be a type that implements Into<i32>
-///  writer.add_entry("Very long value");
-///
-///  //Creates a Vec<u8> out of all the collected data
-///  let result = writer.finalize();
-///
-///  //Try to read the created vector. Will return an error if the CRC32 does not fit
-///  //or if the header is not terminated. Will panic if the memory is corrupted beyond recognition
-///  let reader = MemBufferReader::new(&result).unwrap();
-///
-///  //Will return an error if the selected key could not be found or if the value types dont match
-///  assert_eq!(reader.load_entry::<&str>(0).unwrap(), "Very long value");
-///}
-///```
 
 ///Refers to a position given to every deserialize and serialize operation, can be used to store
 ///data if one does not need to store data in the payload e. g. Field smaller than 8 Bytes
 pub struct Position {
-    pub start: i32,
-    pub end: i32,
+    pub start: i64,
+    pub end: i64,
 }
 
 
 ///Refers to the different types when implementing your own types use an own enum like
-///this:
+///this, built-in types added later on start at 0x1000 to keep these values free:
 ///```rust
 ///use membuffer::MemBufferTypes;
 ///enum MyImplementedTypes {
@@ -63,429 +72,4809 @@ pub enum MemBufferTypes {
     VectorU32,
     VectorU64,
     MemBuffer,
-    LastPreDefienedValue
+    LastPreDefienedValue,
+    ///Kept at the id used by membuffer 0.3
+    UInteger64 = 1021,
+    ///Serde entry encoded with the `Bincode` codec
+    SerdeBincode = 0x1000,
+    ///Serde entry encoded with the `Json` codec
+    SerdeJson,
+    Bool,
+    Char,
+    UInteger8,
+    UInteger16,
+    UInteger32,
+    UInteger128,
+    Integer8,
+    Integer16,
+    Integer64,
+    Integer128,
+    Float32,
+    Float64,
+    VectorI8,
+    VectorU16,
+    VectorI16,
+    VectorI32,
+    VectorI64,
+    VectorU128,
+    VectorI128,
+    VectorF32,
+    VectorF64,
+    ///Marks an entry without value, e. g. a None, the payload of these entries is empty
+    Null,
+    TextList,
+    BytesList,
+    BufferArray,
+    HashMap,
+    SortedSetU64,
+    SortedSetBytes,
+    SortedMapU64,
+    SortedMapBytes,
+    ///Marks a retired entry whose index must not be used again, the payload is empty
+    Tombstone,
 }
 
-impl Into<i32> for MemBufferTypes {
-    fn into(self) -> i32 {
-        self as i32
+impl From<MemBufferTypes> for i32 {
+    fn from(val: MemBufferTypes) -> i32 {
+        val as i32
     }
 }
 
+///Names of the built-in types and of their elements, written into the schema block of buffers
+const BUILTIN_TYPE_NAMES: &[(i32,&str,&str)] = &[
+    (MemBufferTypes::Text as i32, "text", ""),
+    (MemBufferTypes::Integer32 as i32, "i32", ""),
+    (MemBufferTypes::VectorU8 as i32, "[u8]", "u8"),
+    (MemBufferTypes::VectorU32 as i32, "[u32]", "u32"),
+    (MemBufferTypes::VectorU64 as i32, "[u64]", "u64"),
+    (MemBufferTypes::MemBuffer as i32, "membuffer", ""),
+    (MemBufferTypes::UInteger64 as i32, "u64", ""),
+    (MemBufferTypes::SerdeBincode as i32, "serde-bincode", ""),
+    (MemBufferTypes::SerdeJson as i32, "serde-json", ""),
+    (MemBufferTypes::Bool as i32, "bool", ""),
+    (MemBufferTypes::Char as i32, "char", ""),
+    (MemBufferTypes::UInteger8 as i32, "u8", ""),
+    (MemBufferTypes::UInteger16 as i32, "u16", ""),
+    (MemBufferTypes::UInteger32 as i32, "u32", ""),
+    (MemBufferTypes::UInteger128 as i32, "u128", ""),
+    (MemBufferTypes::Integer8 as i32, "i8", ""),
+    (MemBufferTypes::Integer16 as i32, "i16", ""),
+    (MemBufferTypes::Integer64 as i32, "i64", ""),
+    (MemBufferTypes::Integer128 as i32, "i128", ""),
+    (MemBufferTypes::Float32 as i32, "f32", ""),
+    (MemBufferTypes::Float64 as i32, "f64", ""),
+    (MemBufferTypes::VectorI8 as i32, "[i8]", "i8"),
+    (MemBufferTypes::VectorU16 as i32, "[u16]", "u16"),
+    (MemBufferTypes::VectorI16 as i32, "[i16]", "i16"),
+    (MemBufferTypes::VectorI32 as i32, "[i32]", "i32"),
+    (MemBufferTypes::VectorI64 as i32, "[i64]", "i64"),
+    (MemBufferTypes::VectorU128 as i32, "[u128]", "u128"),
+    (MemBufferTypes::VectorI128 as i32, "[i128]", "i128"),
+    (MemBufferTypes::VectorF32 as i32, "[f32]", "f32"),
+    (MemBufferTypes::VectorF64 as i32, "[f64]", "f64"),
+    (MemBufferTypes::Null as i32, "null", ""),
+    (MemBufferTypes::TextList as i32, "text-list", "text"),
+    (MemBufferTypes::BytesList as i32, "bytes-list", "[u8]"),
+    (MemBufferTypes::BufferArray as i32, "buffer-array", "membuffer"),
+    (MemBufferTypes::HashMap as i32, "hash-map", ""),
+    (MemBufferTypes::SortedSetU64 as i32, "sorted-set-u64", "u64"),
+    (MemBufferTypes::SortedSetBytes as i32, "sorted-set-bytes", "[u8]"),
+    (MemBufferTypes::SortedMapU64 as i32, "sorted-map-u64", ""),
+    (MemBufferTypes::SortedMapBytes as i32, "sorted-map-bytes", ""),
+    (MemBufferTypes::Tombstone as i32, "tombstone", ""),
+];
+
+///Returns the name of a built-in type
+pub fn builtin_type_name(type_id: i32) -> Option<&'static str> {
+    BUILTIN_TYPE_NAMES.iter().find(|x| x.0 == type_id).map(|x| x.1)
+}
+
 
 struct InternPosition {
     pub pos: Position,
     pub variable_type: i32,
+    ///CRC32C of the payload, only stored in buffers with FLAG_CHECKSUMS
+    pub checksum: u32,
+}
+
+///Size of one serialized InternPosition in the header: start, end and type as i32 each
+const INTERN_POSITION_SIZE: usize = 12;
+
+///Size of one serialized InternPosition in buffers with FLAG_OFFSETS_64: start and end as u64
+///and type as i32
+const INTERN_POSITION_SIZE_64: usize = 20;
+
+///Largest offset which can be stored in a header with 32 bit offsets
+const MAX_OFFSET_32: usize = i32::MAX as usize;
+
+///Size of the checksum stored after every InternPosition in buffers with FLAG_CHECKSUMS
+const CHECKSUM_SIZE: usize = 4;
+
+///Largest alignment any built-in type requires, nested buffers are aligned to this so the entries
+///inside of them stay aligned as well
+const MAX_ALIGNMENT: usize = std::mem::align_of::<u128>();
+
+//Layout of the header:
+//  magic bytes "MBUF"
+//  format version as little endian u16
+//  two reserved bytes which are always zero
+//  flags as little endian u32
+//  entry count as i32 and CRC32C of the header as u32 in the byte order given by the flags
+//  start, end and type of every entry as i32 in the byte order given by the flags, followed by
+//  the CRC32C of the payload of the entry as u32 if FLAG_CHECKSUMS is set. With FLAG_OFFSETS_64
+//  start and end are stored as u64 instead
+//  the name table if FLAG_NAMES is set, see below
+//  the length of the schema block as u64 followed by the schema block if FLAG_SCHEMA is set, see
+//  BufferSchema
+//The CRC32C of the header covers everything in the header except for itself. Buffers written by
+//membuffer 0.3 have no magic bytes, version and flags, they start with the entry count followed
+//by the entry count minus 0x7AFECAFE and are stored in the native byte order.
+//Layout of the name table, all numbers are u64 in the byte order given by the flags:
+//  length of the name table without this field
+//  capacity of the hash table which is a power of two
+//  index plus one of the named entry in every slot of the hash table, zero for empty slots,
+//  collisions are resolved by linear probing starting at the slot given by the hash of the name
+//  end of the name of every entry relative to the start of the names, unnamed entries have an
+//  empty name
+//  the utf-8 encoded names of all entries
+
+///Magic bytes at the start of every buffer
+const MAGIC: &[u8; 4] = b"MBUF";
+
+///Size of the magic bytes, the version, the reserved bytes and the flags
+const PREFIX_SIZE: usize = 12;
+
+///The version of the format written by this crate
+pub const FORMAT_VERSION: u16 = 1;
+
+///The version reported for buffers written by membuffer 0.3 which have no version in the header
+pub const LEGACY_FORMAT_VERSION: u16 = 0;
+
+///Set in the flags if all numbers in the buffer are stored in big endian byte order
+pub const FLAG_BIG_ENDIAN: u32 = 1;
+
+///Set in the flags if every payload is padded to the alignment of its type
+pub const FLAG_ALIGNED: u32 = 1 << 1;
+
+///Set in the flags if the CRC32C of every payload is stored in the header
+pub const FLAG_CHECKSUMS: u32 = 1 << 2;
+
+///Reserved for buffers with compressed payloads
+pub const FLAG_COMPRESSED: u32 = 1 << 3;
+
+///Set in the flags if the start and end of every entry are stored as 64 bit offsets
+pub const FLAG_OFFSETS_64: u32 = 1 << 4;
+
+///Set in the flags if the header ends with a table of entry names
+pub const FLAG_NAMES: u32 = 1 << 5;
+
+///Set in the flags if the header ends with a schema block describing the entries
+pub const FLAG_SCHEMA: u32 = 1 << 6;
+
+///All flags this version of the crate is able to read, buffers with other flags are rejected
+const SUPPORTED_FLAGS: u32 = FLAG_BIG_ENDIAN | FLAG_ALIGNED | FLAG_CHECKSUMS | FLAG_OFFSETS_64 | FLAG_NAMES | FLAG_SCHEMA;
+
+///Size of every number in the name table
+const NAME_FIELD_SIZE: usize = 8;
+
+///Returns the size of one entry in the header of a buffer with the given flags
+fn position_size(flags: u32) -> usize {
+    let mut size = if flags & FLAG_OFFSETS_64 != 0 { INTERN_POSITION_SIZE_64 } else { INTERN_POSITION_SIZE };
+    if flags & FLAG_CHECKSUMS != 0 {
+        size += CHECKSUM_SIZE;
+    }
+    size
+}
+
+///Returns the offset of the name ends in the name table of a buffer with count entries, None if
+///the table is too short or the capacity of its hash table is not a power of two
+fn names_table_start<B: ByteOrder>(names: &[u8], count: usize) -> Option<usize> {
+    let capacity = usize::try_from(B::read_u64(names.get(..NAME_FIELD_SIZE)?)).ok()?;
+    if !capacity.is_power_of_two() {
+        return None;
+    }
+    let start = capacity.checked_add(1)?.checked_mul(NAME_FIELD_SIZE)?;
+    if start.checked_add(count.checked_mul(NAME_FIELD_SIZE)?)? > names.len() {
+        return None;
+    }
+    Some(start)
+}
+
+///Width of the entry offsets stored in the header of a buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OffsetWidth {
+    ///Use 32 bit offsets and switch to 64 bit offsets if the payload exceeds 2 GiB
+    #[default]
+    Auto,
+    ///Always use 32 bit offsets, `finalize` fails if the payload exceeds 2 GiB
+    Bits32,
+    ///Always use 64 bit offsets
+    Bits64,
+}
+
+///Returns true if the byte order B is big endian
+fn is_big_endian<B: ByteOrder>() -> bool {
+    B::read_u16(&[0,1]) == 1
+}
+
+///Returns true if the byte order B matches the byte order of this machine
+fn is_native<B: ByteOrder>() -> bool {
+    is_big_endian::<B>() == cfg!(target_endian = "big")
 }
 
 
 
 
+///All errors which can occur while writing or reading a memory buffer
 #[derive(Debug, Clone)]
 pub enum MemBufferError {
+    ///The entry has the first type but the second type was requested
     FieldTypeError(i32,i32),
+    ///The buffer does not start with a valid header
     WrongFormat,
+    ///The buffer of the given length ends before the end of its header at header_len
+    TruncatedHeader { len: usize, header_len: usize },
+    ///The header entry with the given key has a negative or inverted range or points past the end
+    ///of the payload
+    InvalidPosition { key: usize, start: i64, end: i64, payload_len: usize },
+    ///The requested key is not smaller than the number of entries
+    KeyOutOfRange { key: usize, len: usize },
+    ///A text entry does not contain valid utf-8
+    InvalidUtf8(std::str::Utf8Error),
+    ///The entry cannot be borrowed as slice as its memory is not aligned to the given alignment
+    Misaligned { alignment: usize },
+    ///The length of the entry is not a multiple of the size of the requested element type
+    InvalidLength { len: usize, element_size: usize },
+    ///The data is stored in a byte order (big endian if true) which does not match the one
+    ///requested by the reader or which cannot be borrowed on this machine
+    WrongByteOrder { big_endian: bool },
+    ///The buffer was written in a format version this crate cannot read
+    UnsupportedVersion { version: u16 },
+    ///The buffer uses features given by these flags which this crate cannot read
+    UnsupportedFlags { flags: u32 },
+    ///The CRC32C stored in the header does not match the header
+    HeaderChecksumMismatch,
+    ///The CRC32C stored for the entry with the given key does not match its payload
+    ChecksumMismatch { key: usize },
+    ///The entry with the given key ends past the largest offset a header with 32 bit offsets can
+    ///store
+    OffsetOverflow { key: usize },
+    ///The payload of the serde entry with the given key could not be decoded
+    Deserialize { key: usize, source: Arc<dyn std::error::Error + Send + Sync> },
+    ///The value could not be encoded as serde entry
+    Serialize(Arc<dyn std::error::Error + Send + Sync>),
+    ///The entry does not hold a valid value of the named type, e. g. a bool other than 0 or 1
+    InvalidValue { type_name: &'static str },
+    ///The offset table of a list with count elements does not fit into the entry of size len
+    TruncatedList { count: u64, len: usize },
+    ///The key at the given index of the input was given more than once
+    DuplicateKey { index: usize },
+    ///The hash table of a map entry is corrupted
+    InvalidMap,
+    ///Map values cannot be nullable, absent values are stored by leaving out their key
+    NullableMapValue,
+    ///No entry has the given name
+    UnknownName { name: String },
+    ///Another entry already has the given name
+    DuplicateName { name: String },
+    ///The name table in the header is corrupted
+    InvalidNameTable,
+    ///The entry with the given key was retired and cannot be loaded as a type which is not
+    ///nullable
+    RetiredEntry { key: usize },
+    ///The field with the given name is required but no value was given for it
+    MissingField { name: &'static str },
 }
 
-impl<'a> std::fmt::Display for MemBufferError {
+impl std::fmt::Display for MemBufferError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
-            MemBufferError::FieldTypeError(x,y) => write!(f,"Memory buffer error: Field has type {} and not requested type {}",x.to_string(),y.to_string()),
-            MemBufferError::WrongFormat => write!(f,"Memory buffer error: Reached end of slice before end of header, memory seems to be corrupted")
+            MemBufferError::FieldTypeError(x,y) => write!(f,"Memory buffer error: Field has type {} and not requested type {}",x,y),
+            MemBufferError::WrongFormat => write!(f,"Memory buffer error: Buffer does not start with a valid header, memory seems to be corrupted"),
+            MemBufferError::TruncatedHeader { len, header_len } => write!(f,"Memory buffer error: Reached end of slice of size {} before end of header at {}, memory seems to be corrupted",len,header_len),
+            MemBufferError::InvalidPosition { key, start, end, payload_len } => write!(f,"Memory buffer error: Entry {} has range {}..{} which does not fit into the payload of size {}",key,start,end,payload_len),
+            MemBufferError::KeyOutOfRange { key, len } => write!(f,"Memory buffer error: Key {} is out of range for {} entries",key,len),
+            MemBufferError::InvalidUtf8(err) => write!(f,"Memory buffer error: Text entry is not valid utf-8: {}",err),
+            MemBufferError::Misaligned { alignment } => write!(f,"Memory buffer error: Entry is not aligned to {} bytes, load it as Cow to get a copy",alignment),
+            MemBufferError::InvalidLength { len, element_size } => write!(f,"Memory buffer error: Entry of size {} is not a multiple of the element size {}",len,element_size),
+            MemBufferError::WrongByteOrder { big_endian } => write!(f,"Memory buffer error: Data is stored in {} endian byte order which does not match the requested one",if *big_endian {"big"} else {"little"}),
+            MemBufferError::UnsupportedVersion { version } => write!(f,"Memory buffer error: Format version {} is not supported, the latest supported version is {}",version,FORMAT_VERSION),
+            MemBufferError::UnsupportedFlags { flags } => write!(f,"Memory buffer error: The buffer uses unsupported features with flags {:#x}",flags),
+            MemBufferError::HeaderChecksumMismatch => write!(f,"Memory buffer error: Checksum of the header does not match, memory seems to be corrupted"),
+            MemBufferError::ChecksumMismatch { key } => write!(f,"Memory buffer error: Checksum of entry {} does not match, memory seems to be corrupted",key),
+            MemBufferError::OffsetOverflow { key } => write!(f,"Memory buffer error: Entry {} does not fit into 32 bit offsets, use 64 bit offsets instead",key),
+            MemBufferError::Deserialize { key, source } => write!(f,"Memory buffer error: Could not decode serde entry {}: {}",key,source),
+            MemBufferError::Serialize(err) => write!(f,"Memory buffer error: Could not encode serde entry: {}",err),
+            MemBufferError::InvalidValue { type_name } => write!(f,"Memory buffer error: Entry does not hold a valid {}",type_name),
+            MemBufferError::TruncatedList { count, len } => write!(f,"Memory buffer error: List with {} elements does not fit into entry of size {}",count,len),
+            MemBufferError::DuplicateKey { index } => write!(f,"Memory buffer error: Key at index {} was already added",index),
+            MemBufferError::InvalidMap => write!(f,"Memory buffer error: Hash table of the map is corrupted"),
+            MemBufferError::NullableMapValue => write!(f,"Memory buffer error: Map values cannot be nullable, leave out the key instead"),
+            MemBufferError::UnknownName { name } => write!(f,"Memory buffer error: No entry is named {:?}",name),
+            MemBufferError::DuplicateName { name } => write!(f,"Memory buffer error: An entry named {:?} was already added",name),
+            MemBufferError::InvalidNameTable => write!(f,"Memory buffer error: Name table of the header is corrupted"),
+            MemBufferError::RetiredEntry { key } => write!(f,"Memory buffer error: Entry {} was retired",key),
+            MemBufferError::MissingField { name } => write!(f,"Memory buffer error: Required field {} was not set",name),
+        }
+    }
+}
+
+impl std::error::Error for MemBufferError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MemBufferError::InvalidUtf8(err) => Some(err),
+            MemBufferError::Deserialize { source, .. } => Some(source.as_ref()),
+            MemBufferError::Serialize(err) => Some(err.as_ref()),
+            _ => None,
         }
     }
 }
 
 
+///Error returned by a serde codec
+pub type CodecError = Box<dyn std::error::Error + Send + Sync>;
+
+///Encoding used for serde entries, every codec stores its entries under its own type id so
+///readers know which codec to decode an entry with. Own codecs can be used by implementing this
+///trait with a type id which is not used by any other type:
+#[cfg_attr(feature = "bincode", doc = "```rust")]
+#[cfg_attr(not(feature = "bincode"), doc = "```ignore")]
+///use membuffer::{MemBufferWriter,MemBufferReader,SerdeCodec,CodecError};
+///use serde::{Serialize,Deserialize};
+///
+///use bincode::Options;
+///
+///struct BigEndianBincode;
+///
+///impl SerdeCodec for BigEndianBincode {
+///    fn get_mem_buffer_type() -> i32 {
+///        100
+///    }
+///
+///    fn encode<T: Serialize>(val: &T) -> Result<Vec<u8>,CodecError> {
+///        Ok(bincode::DefaultOptions::new().with_big_endian().serialize(val)?)
+///    }
+///
+///    fn decode<'a, T: Deserialize<'a>>(data: &'a [u8]) -> Result<T,CodecError> {
+///        Ok(bincode::DefaultOptions::new().with_big_endian().deserialize(data)?)
+///    }
+///}
+///
+///let mut writer = MemBufferWriter::new();
+///writer.add_serde_entry_with::<BigEndianBincode,_>(&42u64).unwrap();
+///let data = writer.finalize().unwrap();
+///
+///let reader = MemBufferReader::new(&data).unwrap();
+///assert_eq!(reader.load_serde_entry_with::<BigEndianBincode,u64>(0).unwrap(), 42);
+///assert!(reader.load_serde_entry::<u64>(0).is_err());
+///```
+pub trait SerdeCodec {
+    ///The type id entries encoded with this codec are stored under
+    fn get_mem_buffer_type() -> i32;
+    fn encode<T: Serialize>(val: &T) -> Result<Vec<u8>,CodecError>;
+    fn decode<'a, T: Deserialize<'a>>(data: &'a [u8]) -> Result<T,CodecError>;
+}
+
+///Encodes serde entries with bincode, this is the codec used by `add_serde_entry`
+#[cfg(feature = "bincode")]
+pub struct Bincode;
+
+#[cfg(feature = "bincode")]
+impl SerdeCodec for Bincode {
+    fn get_mem_buffer_type() -> i32 {
+        MemBufferTypes::SerdeBincode.into()
+    }
+
+    fn encode<T: Serialize>(val: &T) -> Result<Vec<u8>,CodecError> {
+        Ok(bincode::serialize(val)?)
+    }
+
+    fn decode<'a, T: Deserialize<'a>>(data: &'a [u8]) -> Result<T,CodecError> {
+        Ok(bincode::deserialize(data)?)
+    }
+}
+
+///Encodes serde entries as JSON text
+#[cfg(feature = "json")]
+pub struct Json;
+
+#[cfg(feature = "json")]
+impl SerdeCodec for Json {
+    fn get_mem_buffer_type() -> i32 {
+        MemBufferTypes::SerdeJson.into()
+    }
+
+    fn encode<T: Serialize>(val: &T) -> Result<Vec<u8>,CodecError> {
+        Ok(serde_json::to_vec(val)?)
+    }
+
+    fn decode<'a, T: Deserialize<'a>>(data: &'a [u8]) -> Result<T,CodecError> {
+        Ok(serde_json::from_slice(data)?)
+    }
+}
+
+
 pub trait MemBufferDeserialize<'a,T> {
-    fn from_mem_buffer(mem: &'a [u8]) -> Result<T,MemBufferError> where Self: Sized;
+    ///Loads the value from the memory, numbers in the memory are stored in the byte order B
+    fn from_mem_buffer<B: ByteOrder>(mem: &'a [u8]) -> Result<T,MemBufferError> where Self: Sized;
+
+    ///Returns the value for entries stored as null, only nullable types like Option accept them.
+    ///The expected type is the type id this type is stored with.
+    fn from_mem_buffer_null(expected_type: i32) -> Result<T,MemBufferError> where Self: Sized {
+        Err(MemBufferError::FieldTypeError(MemBufferTypes::Null.into(),expected_type))
+    }
 }
 
-impl<'a> MemBufferDeserialize<'a,&'a str> for &str {
-    fn from_mem_buffer(mem: &'a [u8]) -> Result<&'a str,MemBufferError> {
-        //This should always be safe as long as the saved string was utf-8 encoded and no one
-        //messed with the file on disk.
-        unsafe{ Ok(std::str::from_utf8_unchecked(mem)) }
+///Plain numeric types which are stored in arrays in the payload, as any bit pattern is a valid
+///value they can be borrowed from the payload directly if alignment and byte order fit
+trait MemBufferNumeric: Copy {
+    fn read_from<B: ByteOrder>(buf: &[u8]) -> Self;
+    fn write_to<B: ByteOrder>(self, buf: &mut [u8]);
+}
+
+macro_rules! impl_mem_buffer_numeric {
+    ($t:ty, $read:ident, $write:ident) => {
+        impl MemBufferNumeric for $t {
+            fn read_from<B: ByteOrder>(buf: &[u8]) -> Self {
+                B::$read(buf)
+            }
+
+            fn write_to<B: ByteOrder>(self, buf: &mut [u8]) {
+                B::$write(buf, self)
+            }
+        }
+    };
+}
+
+impl_mem_buffer_numeric!(u16, read_u16, write_u16);
+impl_mem_buffer_numeric!(u32, read_u32, write_u32);
+impl_mem_buffer_numeric!(u64, read_u64, write_u64);
+impl_mem_buffer_numeric!(u128, read_u128, write_u128);
+impl_mem_buffer_numeric!(i16, read_i16, write_i16);
+impl_mem_buffer_numeric!(i32, read_i32, write_i32);
+impl_mem_buffer_numeric!(i64, read_i64, write_i64);
+impl_mem_buffer_numeric!(i128, read_i128, write_i128);
+impl_mem_buffer_numeric!(f32, read_f32, write_f32);
+impl_mem_buffer_numeric!(f64, read_f64, write_f64);
+
+//Single bytes have no byte order
+impl MemBufferNumeric for u8 {
+    fn read_from<B: ByteOrder>(buf: &[u8]) -> Self {
+        buf[0]
+    }
+
+    fn write_to<B: ByteOrder>(self, buf: &mut [u8]) {
+        buf[0] = self
+    }
+}
+
+impl MemBufferNumeric for i8 {
+    fn read_from<B: ByteOrder>(buf: &[u8]) -> Self {
+        buf[0] as i8
+    }
+
+    fn write_to<B: ByteOrder>(self, buf: &mut [u8]) {
+        buf[0] = self as u8
+    }
+}
+
+///Checks that the memory holds a whole number of elements of type T, the number of zero sized
+///elements cannot be told from the memory so they are rejected
+fn check_slice_length<T>(mem: &[u8]) -> Result<usize,MemBufferError> {
+    let element_size = std::mem::size_of::<T>();
+    if element_size == 0 || !mem.len().is_multiple_of(element_size) {
+        return Err(MemBufferError::InvalidLength { len: mem.len(), element_size });
+    }
+    Ok(mem.len()/element_size)
+}
+
+///Reads a single number which has to fill the whole memory
+fn read_scalar<B: ByteOrder, T: MemBufferNumeric>(mem: &[u8]) -> Result<T,MemBufferError> {
+    let element_size = std::mem::size_of::<T>();
+    if mem.len() != element_size {
+        return Err(MemBufferError::InvalidLength { len: mem.len(), element_size });
+    }
+    Ok(T::read_from::<B>(mem))
+}
+
+///Writes a single number in the byte order B
+fn scalar_to_bytes<B: ByteOrder, T: MemBufferNumeric>(val: T) -> Cow<'static,[u8]> {
+    let mut buf = vec![0; std::mem::size_of::<T>()];
+    val.write_to::<B>(&mut buf);
+    Cow::Owned(buf)
+}
+
+///Reinterprets the memory as slice of T after checking byte order, length and alignment.
+fn cast_slice<B: ByteOrder, T: MemBufferNumeric>(mem: &[u8]) -> Result<&[T],MemBufferError> {
+    if std::mem::size_of::<T>() > 1 && !is_native::<B>() {
+        return Err(MemBufferError::WrongByteOrder { big_endian: is_big_endian::<B>() });
+    }
+    //Safe as T is a plain numeric type
+    unsafe { cast_native_slice(mem) }
+}
+
+///Reinterprets the memory as slice of T after checking length and alignment.
+///
+///# Safety
+///Every bit pattern must be a valid value of T.
+unsafe fn cast_native_slice<T: Copy>(mem: &[u8]) -> Result<&[T],MemBufferError> {
+    let len = check_slice_length::<T>(mem)?;
+    let alignment = std::mem::align_of::<T>();
+    if mem.as_ptr().align_offset(alignment) != 0 {
+        return Err(MemBufferError::Misaligned { alignment });
+    }
+    Ok(std::slice::from_raw_parts(mem.as_ptr().cast::<T>(), len))
+}
+
+///Views the slice as its bytes in the native byte order.
+///
+///# Safety
+///T must not contain any padding bytes.
+unsafe fn native_bytes<T: Copy>(val: &[T]) -> &[u8] {
+    std::slice::from_raw_parts(val.as_ptr().cast::<u8>(), std::mem::size_of_val(val))
+}
+
+///Borrows the memory as slice of T if it is aligned and in the native byte order and copies it
+///into an owned vector if not
+fn cow_slice<B: ByteOrder, T: MemBufferNumeric>(mem: &[u8]) -> Result<Cow<'_,[T]>,MemBufferError> {
+    match cast_slice::<B,T>(mem) {
+        Ok(slice) => Ok(Cow::Borrowed(slice)),
+        Err(MemBufferError::Misaligned { .. }) | Err(MemBufferError::WrongByteOrder { .. }) => {
+            let copy = mem.chunks_exact(std::mem::size_of::<T>())
+                .map(T::read_from::<B>)
+                .collect();
+            Ok(Cow::Owned(copy))
+        }
+        Err(err) => Err(err)
+    }
+}
+
+///Views a slice of plain numeric values as bytes in the byte order B, this only copies if B is
+///not the native byte order
+fn slice_to_bytes<B: ByteOrder, T: MemBufferNumeric>(val: &[T]) -> Cow<'_,[u8]> {
+    if std::mem::size_of::<T>() == 1 || is_native::<B>() {
+        //Safe as numeric types have no padding
+        return Cow::Borrowed(unsafe{ native_bytes(val) });
     }
+    let size = std::mem::size_of::<T>();
+    let mut buf = vec![0; std::mem::size_of_val(val)];
+    for (x,chunk) in val.iter().zip(buf.chunks_exact_mut(size)) {
+        x.write_to::<B>(chunk);
+    }
+    Cow::Owned(buf)
 }
 
-impl<'a> MemBufferDeserialize<'a,i32> for i32 {
-    fn from_mem_buffer(mem: &'a [u8]) -> Result<i32,MemBufferError> {
-        //Fast load integer since no memory is required to store integer
-        Ok(NativeEndian::read_i32(mem))
+impl<'a> MemBufferDeserialize<'a,&'a str> for &str {
+    fn from_mem_buffer<B: ByteOrder>(mem: &'a [u8]) -> Result<&'a str,MemBufferError> {
+        //The file could come from anywhere, use MemBufferReader::load_str_unchecked to skip this
+        //check for already verified buffers
+        std::str::from_utf8(mem).map_err(MemBufferError::InvalidUtf8)
     }
 }
 
-impl<'a> MemBufferDeserialize<'a,u64> for u64 {
-    fn from_mem_buffer(mem: &'a [u8]) -> Result<u64,MemBufferError> {
-        //Fast load integer since no memory is required to store integer
-        Ok(NativeEndian::read_u64(mem))
+impl<'a> MemBufferDeserialize<'a,String> for String {
+    fn from_mem_buffer<B: ByteOrder>(mem: &'a [u8]) -> Result<String,MemBufferError> {
+        Ok(<&str>::from_mem_buffer::<B>(mem)?.to_owned())
     }
 }
 
 impl<'a> MemBufferDeserialize<'a,&'a [u8]> for &[u8] {
-    fn from_mem_buffer(mem: &'a [u8]) -> Result<&'a [u8],MemBufferError> {
+    fn from_mem_buffer<B: ByteOrder>(mem: &'a [u8]) -> Result<&'a [u8],MemBufferError> {
         Ok(mem)
     }
 }
 
-impl<'a> MemBufferDeserialize<'a,&'a [u64]> for &[u64] {
-    fn from_mem_buffer(mem: &'a [u8]) -> Result<&'a [u64],MemBufferError> {
-        let val: *const u8 = mem.as_ptr();
-        let cast_memory = val.cast::<u64>();
-        //Divide by eight as u64 should be 8 bytes on any system
-        let mem_length = mem.len()>>3;
+//Bytes never need to be copied, Cow always borrows them
+impl<'a> MemBufferDeserialize<'a,Cow<'a,[u8]>> for Cow<'a,[u8]> {
+    fn from_mem_buffer<B: ByteOrder>(mem: &'a [u8]) -> Result<Cow<'a,[u8]>,MemBufferError> {
+        Ok(Cow::Borrowed(mem))
+    }
+}
+
+impl<'a> MemBufferDeserialize<'a,Vec<u8>> for Vec<u8> {
+    fn from_mem_buffer<B: ByteOrder>(mem: &'a [u8]) -> Result<Vec<u8>,MemBufferError> {
+        Ok(mem.to_vec())
+    }
+}
+
+impl<'a> MemBufferDeserialize<'a,Box<[u8]>> for Box<[u8]> {
+    fn from_mem_buffer<B: ByteOrder>(mem: &'a [u8]) -> Result<Box<[u8]>,MemBufferError> {
+        Ok(mem.into())
+    }
+}
+
+///Implements loading and storing a single number as entry of the given type
+macro_rules! impl_mem_buffer_scalar {
+    ($t:ty, $type:ident) => {
+        impl<'a> MemBufferDeserialize<'a,$t> for $t {
+            fn from_mem_buffer<B: ByteOrder>(mem: &'a [u8]) -> Result<$t,MemBufferError> {
+                read_scalar::<B,$t>(mem)
+            }
+        }
+
+        impl MemBufferSerialize for $t {
+            fn to_mem_buffer<'a, B: ByteOrder>(&'a self) -> Cow<'a,[u8]> {
+                scalar_to_bytes::<B,$t>(*self)
+            }
+
+            fn get_mem_buffer_type() -> i32 {
+                MemBufferTypes::$type.into()
+            }
+        }
+    };
+}
+
+///Implements loading and storing slices of numbers as entry of the given type, slices are
+///borrowed zero copy while Cow copies them if alignment or byte order do not fit
+macro_rules! impl_mem_buffer_slice {
+    ($t:ty, $type:ident) => {
+        impl<'a> MemBufferDeserialize<'a,&'a [$t]> for &[$t] {
+            fn from_mem_buffer<B: ByteOrder>(mem: &'a [u8]) -> Result<&'a [$t],MemBufferError> {
+                cast_slice::<B,$t>(mem)
+            }
+        }
+
+        impl<'a> MemBufferDeserialize<'a,Cow<'a,[$t]>> for Cow<'a,[$t]> {
+            fn from_mem_buffer<B: ByteOrder>(mem: &'a [u8]) -> Result<Cow<'a,[$t]>,MemBufferError> {
+                cow_slice::<B,$t>(mem)
+            }
+        }
+
+        impl MemBufferSerialize for &[$t] {
+            fn to_mem_buffer<'a, B: ByteOrder>(&'a self) -> Cow<'a,[u8]> {
+                slice_to_bytes::<B,$t>(self)
+            }
+
+            fn get_mem_buffer_type() -> i32 {
+                MemBufferTypes::$type.into()
+            }
+
+            fn get_mem_buffer_alignment() -> usize {
+                std::mem::align_of::<$t>()
+            }
+        }
+
+        impl<'a> MemBufferDeserialize<'a,Vec<$t>> for Vec<$t> {
+            fn from_mem_buffer<B: ByteOrder>(mem: &'a [u8]) -> Result<Vec<$t>,MemBufferError> {
+                Ok(cow_slice::<B,$t>(mem)?.into_owned())
+            }
+        }
+
+        impl<'a> MemBufferDeserialize<'a,Box<[$t]>> for Box<[$t]> {
+            fn from_mem_buffer<B: ByteOrder>(mem: &'a [u8]) -> Result<Box<[$t]>,MemBufferError> {
+                Ok(Vec::<$t>::from_mem_buffer::<B>(mem)?.into_boxed_slice())
+            }
+        }
+
+        impl_mem_buffer_owned_slice!(Cow<'_,[$t]>, $t);
+        impl_mem_buffer_owned_slice!(Vec<$t>, $t);
+        impl_mem_buffer_owned_slice!(Box<[$t]>, $t);
+    };
+}
+
+///Implements storing an owned container of numbers with the same type as a slice of them
+macro_rules! impl_mem_buffer_owned_slice {
+    ($container:ty, $t:ty) => {
+        impl MemBufferSerialize for $container {
+            fn to_mem_buffer<'a, B: ByteOrder>(&'a self) -> Cow<'a,[u8]> {
+                slice_to_bytes::<B,$t>(self)
+            }
+
+            fn get_mem_buffer_type() -> i32 {
+                <&[$t]>::get_mem_buffer_type()
+            }
+
+            fn get_mem_buffer_alignment() -> usize {
+                <&[$t]>::get_mem_buffer_alignment()
+            }
+        }
+    };
+}
 
-        //This should always be safe as long as no one messed with the serialized data
-        Ok(unsafe{std::slice::from_raw_parts(cast_memory, mem_length as usize)})
+impl_mem_buffer_scalar!(u8, UInteger8);
+impl_mem_buffer_scalar!(u16, UInteger16);
+impl_mem_buffer_scalar!(u32, UInteger32);
+impl_mem_buffer_scalar!(u64, UInteger64);
+impl_mem_buffer_scalar!(u128, UInteger128);
+impl_mem_buffer_scalar!(i8, Integer8);
+impl_mem_buffer_scalar!(i16, Integer16);
+impl_mem_buffer_scalar!(i32, Integer32);
+impl_mem_buffer_scalar!(i64, Integer64);
+impl_mem_buffer_scalar!(i128, Integer128);
+impl_mem_buffer_scalar!(f32, Float32);
+impl_mem_buffer_scalar!(f64, Float64);
+
+impl_mem_buffer_slice!(u16, VectorU16);
+impl_mem_buffer_slice!(u32, VectorU32);
+impl_mem_buffer_slice!(u64, VectorU64);
+impl_mem_buffer_slice!(u128, VectorU128);
+impl_mem_buffer_slice!(i8, VectorI8);
+impl_mem_buffer_slice!(i16, VectorI16);
+impl_mem_buffer_slice!(i32, VectorI32);
+impl_mem_buffer_slice!(i64, VectorI64);
+impl_mem_buffer_slice!(i128, VectorI128);
+impl_mem_buffer_slice!(f32, VectorF32);
+impl_mem_buffer_slice!(f64, VectorF64);
+
+impl<'a> MemBufferDeserialize<'a,bool> for bool {
+    fn from_mem_buffer<B: ByteOrder>(mem: &'a [u8]) -> Result<bool,MemBufferError> {
+        match read_scalar::<B,u8>(mem)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(MemBufferError::InvalidValue { type_name: "bool" })
+        }
     }
 }
 
-impl<'a> MemBufferDeserialize<'a,&'a [u32]> for &[u32] {
-    fn from_mem_buffer(mem: &'a [u8]) -> Result<&'a [u32],MemBufferError> {
-        let val: *const u8 = mem.as_ptr();
-        let cast_memory = val.cast::<u32>();
-        //Divide by four as u32 should be 4 bytes on any system
-        let mem_length = mem.len()>>2;
+impl MemBufferSerialize for bool {
+    fn to_mem_buffer<'a, B: ByteOrder>(&'a self) -> Cow<'a,[u8]> {
+        scalar_to_bytes::<B,u8>(*self as u8)
+    }
+
+    fn get_mem_buffer_type() -> i32 {
+        MemBufferTypes::Bool.into()
+    }
+}
 
-        //This should always be safe as long as no one messed with the serialized data
-        Ok(unsafe{std::slice::from_raw_parts(cast_memory, mem_length as usize)})
+impl<'a> MemBufferDeserialize<'a,char> for char {
+    fn from_mem_buffer<B: ByteOrder>(mem: &'a [u8]) -> Result<char,MemBufferError> {
+        std::char::from_u32(read_scalar::<B,u32>(mem)?).ok_or(MemBufferError::InvalidValue { type_name: "char" })
     }
 }
 
-impl<'a> MemBufferDeserialize<'a,MemBufferReader<'a>> for MemBufferReader<'a> {
-    fn from_mem_buffer(mem: &'a [u8]) -> Result<MemBufferReader<'a>,MemBufferError> {
-        let reader = MemBufferReader::new(mem)?;
-        Ok(reader)
+impl MemBufferSerialize for char {
+    fn to_mem_buffer<'a, B: ByteOrder>(&'a self) -> Cow<'a,[u8]> {
+        scalar_to_bytes::<B,u32>(*self as u32)
+    }
+
+    fn get_mem_buffer_type() -> i32 {
+        MemBufferTypes::Char.into()
     }
 }
 
-///The reader which is used for reading the memory area produced by the writer, **Important notice:
-///The reader uses the native endian of the system used therefore sending between big endian and
-///little endian systems wont work**
+///Marks plain old data types which can be stored as records with `&T` or in arrays with `&[T]`
+///and are borrowed from the payload without copying when loaded. Records are stored in the
+///native byte order of the writing machine independent of the byte order of the buffer, the
+///type id of the entries includes the byte order of the machine, the name and the size of the
+///type so loading records written on a machine with another byte order or with a different
+///layout fails with a FieldTypeError.
 ///```rust
-///use membuffer::{MemBufferWriter,MemBufferReader};
+///use membuffer::{MemBufferWriter,MemBufferReader,MemBufferPod};
 ///
-///let mut data = MemBufferWriter::new();
-///data.add_entry("Add some data to save to file or send over the network");
-///let data_vec = data.finalize();
-/////The reader is type sensitive
-///let reader = MemBufferReader::new(&data_vec).unwrap();
-/////We load the first entry, try not to get this mixed up
-///assert_eq!(reader.load_entry::<&str>(0).unwrap(),"Add some data to save to file or send over the network");
+///#[derive(Clone,Copy,Debug,PartialEq)]
+///#[repr(C)]
+///struct Sample {
+///    timestamp: u64,
+///    value: f32,
+///    weight: f32,
+///}
+///
+/////Safe as Sample is repr(C) without padding and any bit pattern is a valid Sample
+///unsafe impl MemBufferPod for Sample {
+///    const POD_NAME: &'static str = "Sample";
+///}
+///
+///let samples = [Sample { timestamp: 1, value: 0.5, weight: 1.0 }, Sample { timestamp: 2, value: 1.5, weight: 0.5 }];
+///let mut writer = MemBufferWriter::new();
+///writer.add_entry(&samples[..]);
+///writer.add_entry(&samples[0]);
+///let data = writer.finalize().unwrap();
+///
+///let reader = MemBufferReader::new(&data).unwrap();
+///assert_eq!(reader.load_entry::<&[Sample]>(0).unwrap(), &samples);
+///assert_eq!(reader.load_entry::<&Sample>(1).unwrap(), &samples[0]);
 ///```
-pub struct MemBufferReader<'a> {
-    offsets: &'a [InternPosition],
-    data: &'a [u8]
+///
+///# Safety
+///The type must not contain padding bytes, pointers or references, and every bit pattern must
+///be a valid value of the type which rules out fields like bool, char or enums. Use
+///`#[repr(C)]` or `#[repr(transparent)]` to get a defined layout. Zero sized types can be
+///written but loading them fails with `InvalidLength` as their number is not stored.
+pub unsafe trait MemBufferPod: Copy + 'static {
+    ///Name of the record type, has to stay the same as long as buffers with the type are read
+    const POD_NAME: &'static str;
 }
 
-impl<'a> MemBufferReader<'a> {
-    ///Deserialize data from a buffer to an i32 integer
-    pub fn deserialize_i32_from(mut buffer: &[u8]) -> i32 {
-        buffer.read_i32::<NativeEndian>().unwrap()
+///Computes the type id of entries holding records of type T, ids of records always have the
+///highest bit set to stay apart from the built-in and user defined types
+fn pod_type_id<T: MemBufferPod>() -> i32 {
+    let crc = crc32c::crc32c(T::POD_NAME.as_bytes());
+    let crc = crc32c::crc32c_append(crc, &(std::mem::size_of::<T>() as u64).to_le_bytes());
+    let crc = crc32c::crc32c_append(crc, &[cfg!(target_endian = "big") as u8]);
+    (crc | 0x8000_0000) as i32
+}
+
+impl<'a,T: MemBufferPod> MemBufferDeserialize<'a,&'a [T]> for &[T] {
+    fn from_mem_buffer<B: ByteOrder>(mem: &'a [u8]) -> Result<&'a [T],MemBufferError> {
+        //Safe as every bit pattern is a valid T by the contract of MemBufferPod
+        unsafe { cast_native_slice(mem) }
+    }
+}
+
+impl<'a,T: MemBufferPod> MemBufferDeserialize<'a,&'a T> for &T {
+    fn from_mem_buffer<B: ByteOrder>(mem: &'a [u8]) -> Result<&'a T,MemBufferError> {
+        let element_size = std::mem::size_of::<T>();
+        if mem.len() != element_size {
+            return Err(MemBufferError::InvalidLength { len: mem.len(), element_size });
+        }
+        let slice: &'a [T] = <&[T]>::from_mem_buffer::<B>(mem)?;
+        Ok(&slice[0])
+    }
+}
+
+impl<T: MemBufferPod> MemBufferSerialize for &[T] {
+    fn to_mem_buffer<'a, B: ByteOrder>(&'a self) -> Cow<'a,[u8]> {
+        //Safe as T has no padding by the contract of MemBufferPod
+        Cow::Borrowed(unsafe { native_bytes(self) })
+    }
+
+    fn get_mem_buffer_type() -> i32 {
+        pod_type_id::<T>()
+    }
+
+    fn get_mem_buffer_alignment() -> usize {
+        std::mem::align_of::<T>()
+    }
+}
+
+impl<T: MemBufferPod> MemBufferSerialize for &T {
+    fn to_mem_buffer<'a, B: ByteOrder>(&'a self) -> Cow<'a,[u8]> {
+        //Safe as T has no padding by the contract of MemBufferPod
+        Cow::Borrowed(unsafe { native_bytes(std::slice::from_ref(*self)) })
+    }
+
+    fn get_mem_buffer_type() -> i32 {
+        pod_type_id::<T>()
+    }
+
+    fn get_mem_buffer_alignment() -> usize {
+        std::mem::align_of::<T>()
+    }
+}
+
+//Layout of list entries:
+//  number of elements as u64
+//  end offset of every element relative to the start of the data as u64
+//  the data of all elements concatenated
+//All numbers are stored in the byte order of the buffer, the start of an element is the end of
+//the one before it.
+
+///Size of the element count and of every offset in a list entry
+const LIST_OFFSET_SIZE: usize = 8;
+
+///Zero copy view on a list of byte slices stored in one entry, elements are located in O(1)
+///through the offset table and checked when accessed.
+#[derive(Clone,Copy,Debug)]
+pub struct BytesList<'a> {
+    offsets: &'a [u8],
+    data: &'a [u8],
+    big_endian: bool,
+}
+
+impl<'a> BytesList<'a> {
+    fn from_mem_buffer_with<B: ByteOrder>(mem: &'a [u8]) -> Result<BytesList<'a>,MemBufferError> {
+        if mem.len() < LIST_OFFSET_SIZE {
+            return Err(MemBufferError::InvalidLength { len: mem.len(), element_size: LIST_OFFSET_SIZE });
+        }
+        let count = B::read_u64(mem);
+        let table_end = usize::try_from(count).ok()
+            .and_then(|x| x.checked_mul(LIST_OFFSET_SIZE))
+            .and_then(|x| x.checked_add(LIST_OFFSET_SIZE));
+        match table_end {
+            Some(table_end) if table_end <= mem.len() => Ok(BytesList {
+                offsets: &mem[LIST_OFFSET_SIZE..table_end],
+                data: &mem[table_end..],
+                big_endian: is_big_endian::<B>(),
+            }),
+            _ => Err(MemBufferError::TruncatedList { count, len: mem.len() })
+        }
+    }
+
+    fn offset(&self, index: usize) -> u64 {
+        let raw = &self.offsets[index*LIST_OFFSET_SIZE..];
+        if self.big_endian { byteorder::BigEndian::read_u64(raw) } else { LittleEndian::read_u64(raw) }
     }
 
     pub fn len(&self) -> usize {
-        self.offsets.len()
+        self.offsets.len()/LIST_OFFSET_SIZE
     }
 
-    pub fn payload_len(&self) -> usize {
-        self.data.len()
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
     }
-    
-    ///Internal load function this is needed to enable loading nested MemBufferWriters which does
-    ///not implement the Deserialize trait
-    fn intern_load_entry<X: MemBufferDeserialize<'a,X>>(&self, key: usize, expected_type: i32) -> Result<X,MemBufferError> {
-        let entry = &self.offsets[key];
-        let is_type = entry.variable_type;
-        if is_type != expected_type {
-            return Err(MemBufferError::FieldTypeError(is_type,expected_type));
+
+    ///Returns the element with the given index, fails if the index is out of range or if the
+    ///offsets of the element do not fit into the data of the list
+    pub fn get(&self, index: usize) -> Result<&'a [u8],MemBufferError> {
+        if index >= self.len() {
+            return Err(MemBufferError::KeyOutOfRange { key: index, len: self.len() });
         }
-        return X::from_mem_buffer(&self.data[entry.pos.start as usize..entry.pos.end as usize]);
+        let start = if index == 0 { 0 } else { self.offset(index-1) };
+        let end = self.offset(index);
+        if start > end || end > self.data.len() as u64 {
+            return Err(MemBufferError::InvalidPosition { key: index, start: start as i64, end: end as i64, payload_len: self.data.len() });
+        }
+        Ok(&self.data[start as usize..end as usize])
     }
 
-    ///Load one entry with the given type, expecting the serializable trait as well to determine
-    ///the integer type, when doing polymorphismus of structures use the same integer for multiple
-    ///types
-    pub fn load_entry<X: MemBufferDeserialize<'a,X> + MemBufferSerialize>(&self,key: usize) -> Result<X,MemBufferError> {
-        self.intern_load_entry(key.into(), X::get_mem_buffer_type())
+    pub fn iter(&self) -> impl Iterator<Item=Result<&'a [u8],MemBufferError>> + '_ {
+        (0..self.len()).map(move |index| self.get(index))
     }
+}
 
-    ///Loads an entry stored with serde_json and returns it.
-    pub fn load_serde_entry<T: Deserialize<'a>>(&self,key: usize) -> Result<T,MemBufferError> {
-        let data: &[u8] = self.load_entry(key.into())?;
-        Ok(bincode::deserialize(data).unwrap())
+///Zero copy view on a list of strings stored in one entry, see `BytesList`. Every element is
+///checked to be valid utf-8 when it is accessed.
+///```rust
+///use membuffer::{MemBufferWriter,MemBufferReader,StrList};
+///
+///let mut writer = MemBufferWriter::new();
+///writer.add_entry(&["Hello","World"][..]);
+///let data = writer.finalize().unwrap();
+///
+///let reader = MemBufferReader::new(&data).unwrap();
+///let list: StrList = reader.load_entry(0).unwrap();
+///assert_eq!(list.len(), 2);
+///assert_eq!(list.get(1).unwrap(), "World");
+///```
+#[derive(Clone,Copy,Debug)]
+pub struct StrList<'a> {
+    list: BytesList<'a>,
+}
+
+impl<'a> StrList<'a> {
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    ///Returns the string with the given index, fails if the index is out of range, the offsets
+    ///are corrupted or if the element is not valid utf-8
+    pub fn get(&self, index: usize) -> Result<&'a str,MemBufferError> {
+        std::str::from_utf8(self.list.get(index)?).map_err(MemBufferError::InvalidUtf8)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=Result<&'a str,MemBufferError>> + '_ {
+        (0..self.len()).map(move |index| self.get(index))
+    }
+}
+
+impl<'a> MemBufferDeserialize<'a,BytesList<'a>> for BytesList<'a> {
+    fn from_mem_buffer<B: ByteOrder>(mem: &'a [u8]) -> Result<BytesList<'a>,MemBufferError> {
+        BytesList::from_mem_buffer_with::<B>(mem)
+    }
+}
+
+impl<'a> MemBufferDeserialize<'a,StrList<'a>> for StrList<'a> {
+    fn from_mem_buffer<B: ByteOrder>(mem: &'a [u8]) -> Result<StrList<'a>,MemBufferError> {
+        Ok(StrList { list: BytesList::from_mem_buffer_with::<B>(mem)? })
+    }
+}
+
+///Writes the elements as list entry in the byte order B
+fn list_to_bytes<'a, B: ByteOrder, I: ExactSizeIterator<Item=&'a [u8]> + Clone>(items: I) -> Cow<'static,[u8]> {
+    let data_len: usize = items.clone().map(|x| x.len()).sum();
+    let mut buf = Vec::with_capacity(LIST_OFFSET_SIZE*(items.len()+1) + data_len);
+    buf.write_u64::<B>(items.len() as u64).unwrap();
+    let mut end = 0;
+    for x in items.clone() {
+        end += x.len();
+        buf.write_u64::<B>(end as u64).unwrap();
+    }
+    for x in items {
+        buf.extend_from_slice(x);
+    }
+    Cow::Owned(buf)
+}
+
+///Implements storing slices of strings or byte slices as list entries
+macro_rules! impl_mem_buffer_list {
+    ($t:ty, $type:ident, $as_bytes:expr) => {
+        impl MemBufferSerialize for &[$t] {
+            fn to_mem_buffer<'a, B: ByteOrder>(&'a self) -> Cow<'a,[u8]> {
+                list_to_bytes::<B,_>(self.iter().map($as_bytes))
+            }
+
+            fn get_mem_buffer_type() -> i32 {
+                MemBufferTypes::$type.into()
+            }
+        }
+    };
+}
+
+impl_mem_buffer_list!(&str, TextList, |x: &&str| x.as_bytes());
+impl_mem_buffer_list!(String, TextList, |x: &String| x.as_bytes());
+impl_mem_buffer_list!(&[u8], BytesList, |x: &&[u8]| *x);
+impl_mem_buffer_list!(Vec<u8>, BytesList, |x: &Vec<u8>| &x[..]);
+
+impl MemBufferSerialize for BytesList<'_> {
+    //The view could come from a buffer with another byte order, therefore the count and the
+    //offsets are written again in the byte order B
+    fn to_mem_buffer<'a, B: ByteOrder>(&'a self) -> Cow<'a,[u8]> {
+        let mut buf = Vec::with_capacity(LIST_OFFSET_SIZE + self.offsets.len() + self.data.len());
+        buf.write_u64::<B>(self.len() as u64).unwrap();
+        for index in 0..self.len() {
+            buf.write_u64::<B>(self.offset(index)).unwrap();
+        }
+        buf.extend_from_slice(self.data);
+        Cow::Owned(buf)
+    }
+
+    fn get_mem_buffer_type() -> i32 {
+        MemBufferTypes::BytesList.into()
+    }
+}
+
+impl MemBufferSerialize for StrList<'_> {
+    fn to_mem_buffer<'a, B: ByteOrder>(&'a self) -> Cow<'a,[u8]> {
+        self.list.to_mem_buffer::<B>()
+    }
+
+    fn get_mem_buffer_type() -> i32 {
+        MemBufferTypes::TextList.into()
+    }
+}
+
+//Layout of buffer array entries:
+//  number of buffers as u64
+//  start and end offset of every buffer relative to the start of the data as u64
+//  the buffers, each one aligned to MAX_ALIGNMENT relative to the start of the entry
+//All numbers are stored in the byte order of the buffer.
+
+///Zero copy view on an array of nested buffers stored in one entry, a buffer is located in O(1)
+///through the offset table and only its own header is read when it is accessed.
+#[derive(Clone,Copy)]
+pub struct BufferArray<'a,B: ByteOrder = LittleEndian> {
+    offsets: &'a [u8],
+    data: &'a [u8],
+    byte_order: PhantomData<B>
+}
+
+impl<'a,B: ByteOrder> BufferArray<'a,B> {
+    pub fn len(&self) -> usize {
+        self.offsets.len()/(2*LIST_OFFSET_SIZE)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    ///Returns a reader for the buffer with the given index, fails if the index is out of range or
+    ///if the buffer is not valid
+    pub fn get(&self, index: usize) -> Result<MemBufferReader<'a,B>,MemBufferError> {
+        if index >= self.len() {
+            return Err(MemBufferError::KeyOutOfRange { key: index, len: self.len() });
+        }
+        let raw = &self.offsets[index*2*LIST_OFFSET_SIZE..];
+        let (start,end) = (B::read_u64(raw),B::read_u64(&raw[LIST_OFFSET_SIZE..]));
+        if start > end || end > self.data.len() as u64 {
+            return Err(MemBufferError::InvalidPosition { key: index, start: start as i64, end: end as i64, payload_len: self.data.len() });
+        }
+        MemBufferReader::with_byte_order(&self.data[start as usize..end as usize])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=Result<MemBufferReader<'a,B>,MemBufferError>> + '_ {
+        (0..self.len()).map(move |index| self.get(index))
+    }
+}
+
+impl<B: ByteOrder> std::fmt::Debug for BufferArray<'_,B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f,"Found buffer array with {} buffers",self.len())
+    }
+}
+
+impl<'a,R: ByteOrder> MemBufferDeserialize<'a,BufferArray<'a,R>> for BufferArray<'a,R> {
+    fn from_mem_buffer<B: ByteOrder>(mem: &'a [u8]) -> Result<BufferArray<'a,R>,MemBufferError> {
+        if is_big_endian::<B>() != is_big_endian::<R>() {
+            return Err(MemBufferError::WrongByteOrder { big_endian: is_big_endian::<B>() });
+        }
+        if mem.len() < LIST_OFFSET_SIZE {
+            return Err(MemBufferError::InvalidLength { len: mem.len(), element_size: LIST_OFFSET_SIZE });
+        }
+        let count = B::read_u64(mem);
+        let table_end = usize::try_from(count).ok()
+            .and_then(|x| x.checked_mul(2*LIST_OFFSET_SIZE))
+            .and_then(|x| x.checked_add(LIST_OFFSET_SIZE));
+        match table_end {
+            Some(table_end) if table_end <= mem.len() => Ok(BufferArray {
+                offsets: &mem[LIST_OFFSET_SIZE..table_end],
+                data: &mem[table_end..],
+                byte_order: PhantomData
+            }),
+            _ => Err(MemBufferError::TruncatedList { count, len: mem.len() })
+        }
+    }
+}
+
+//Layout of map entries:
+//  number of pairs and number of slots as u64, the type id of the values as i32 and four bytes
+//  of padding
+//  the hash table with the hash of the key and the index of the pair plus one as u64 for every
+//  slot, empty slots have index zero. Collisions are resolved by linear probing
+//  start and end of the key and start and end of the value of every pair as u64 relative to the
+//  start of the data
+//  keys and values of all pairs, every value is aligned to the alignment of its type relative to
+//  the start of the entry
+//All numbers are stored in the byte order of the buffer.
+
+const MAP_HEADER_SIZE: usize = 24;
+const MAP_SLOT_SIZE: usize = 16;
+const MAP_RECORD_SIZE: usize = 32;
+
+///Hash of the keys of map entries, this is part of the format and must never change
+fn map_hash(key: &[u8]) -> u64 {
+    crc32c::crc32c(key) as u64
+}
+
+///Zero copy view on a map entry, looking up a key only reads the probed slots of the hash table,
+///the record of the matching pair and its key.
+#[derive(Clone,Copy,Debug)]
+pub struct MemBufferMap<'a> {
+    slots: &'a [u8],
+    records: &'a [u8],
+    data: &'a [u8],
+    value_type: i32,
+    big_endian: bool,
+}
+
+impl<'a> MemBufferMap<'a> {
+    fn read_u64(&self, raw: &[u8]) -> u64 {
+        if self.big_endian { byteorder::BigEndian::read_u64(raw) } else { LittleEndian::read_u64(raw) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()/MAP_RECORD_SIZE
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    ///The type id all values of the map are stored with
+    pub fn value_type(&self) -> i32 {
+        self.value_type
+    }
+
+    ///Returns the key and the value of the pair with the given index in insertion order
+    pub fn get_index(&self, index: usize) -> Result<(&'a [u8],&'a [u8]),MemBufferError> {
+        if index >= self.len() {
+            return Err(MemBufferError::KeyOutOfRange { key: index, len: self.len() });
+        }
+        let raw = &self.records[index*MAP_RECORD_SIZE..];
+        let mut ranges = [0u64; 4];
+        for (x,range) in ranges.iter_mut().enumerate() {
+            *range = self.read_u64(&raw[x*8..]);
+        }
+        let payload_len = self.data.len();
+        for range in ranges.chunks_exact(2) {
+            if range[0] > range[1] || range[1] > payload_len as u64 {
+                return Err(MemBufferError::InvalidPosition { key: index, start: range[0] as i64, end: range[1] as i64, payload_len });
+            }
+        }
+        Ok((&self.data[ranges[0] as usize..ranges[1] as usize],&self.data[ranges[2] as usize..ranges[3] as usize]))
+    }
+
+    ///Returns the raw value stored for the key
+    pub fn get<K: AsRef<[u8]> + ?Sized>(&self, key: &K) -> Result<Option<&'a [u8]>,MemBufferError> {
+        let key = key.as_ref();
+        let capacity = self.slots.len()/MAP_SLOT_SIZE;
+        if capacity == 0 {
+            return Ok(None);
+        }
+        let hash = map_hash(key);
+        let mut slot = hash as usize & (capacity-1);
+        //Probing stops at the first empty slot, a corrupted table without empty slots is only
+        //walked once
+        for _ in 0..capacity {
+            let raw = &self.slots[slot*MAP_SLOT_SIZE..];
+            let index = self.read_u64(&raw[8..]);
+            if index == 0 {
+                return Ok(None);
+            }
+            if self.read_u64(raw) == hash {
+                let index = usize::try_from(index-1).map_err(|_| MemBufferError::InvalidMap)?;
+                let (stored,val) = self.get_index(index).map_err(|_| MemBufferError::InvalidMap)?;
+                if stored == key {
+                    return Ok(Some(val));
+                }
+            }
+            slot = (slot+1) & (capacity-1);
+        }
+        Ok(None)
+    }
+
+    ///Loads the value stored for the key as V, fails with `FieldTypeError` if the values of the
+    ///map have another type
+    pub fn get_as<V: MemBufferDeserialize<'a,V> + MemBufferSerialize, K: AsRef<[u8]> + ?Sized>(&self, key: &K) -> Result<Option<V>,MemBufferError> {
+        load_map_value(self.value_type, self.big_endian, self.get(key)?)
+    }
+
+    pub fn contains_key<K: AsRef<[u8]> + ?Sized>(&self, key: &K) -> Result<bool,MemBufferError> {
+        Ok(self.get(key)?.is_some())
+    }
+
+    ///Iterates over all pairs in insertion order
+    pub fn iter(&self) -> impl Iterator<Item=Result<(&'a [u8],&'a [u8]),MemBufferError>> + '_ {
+        (0..self.len()).map(move |index| self.get_index(index))
+    }
+}
+
+impl<'a> MemBufferDeserialize<'a,MemBufferMap<'a>> for MemBufferMap<'a> {
+    fn from_mem_buffer<B: ByteOrder>(mem: &'a [u8]) -> Result<MemBufferMap<'a>,MemBufferError> {
+        if mem.len() < MAP_HEADER_SIZE {
+            return Err(MemBufferError::InvalidLength { len: mem.len(), element_size: MAP_HEADER_SIZE });
+        }
+        let count = B::read_u64(mem);
+        let capacity = B::read_u64(&mem[8..]);
+        if count > capacity || (capacity != 0 && !capacity.is_power_of_two()) {
+            return Err(MemBufferError::InvalidMap);
+        }
+        let slots_end = usize::try_from(capacity).ok()
+            .and_then(|x| x.checked_mul(MAP_SLOT_SIZE))
+            .and_then(|x| x.checked_add(MAP_HEADER_SIZE));
+        let table_end = usize::try_from(count).ok()
+            .and_then(|x| x.checked_mul(MAP_RECORD_SIZE))
+            .and_then(|x| slots_end?.checked_add(x));
+        match (slots_end,table_end) {
+            (Some(slots_end),Some(table_end)) if table_end <= mem.len() => Ok(MemBufferMap {
+                slots: &mem[MAP_HEADER_SIZE..slots_end],
+                records: &mem[slots_end..table_end],
+                data: &mem[table_end..],
+                value_type: B::read_i32(&mem[16..]),
+                big_endian: is_big_endian::<B>(),
+            }),
+            _ => Err(MemBufferError::TruncatedList { count, len: mem.len() })
+        }
+    }
+}
+
+///Decodes a value of a map entry whose values are stored with the given type id, the type is
+///checked even if the key is missing so both kinds of maps report a mismatch the same way
+fn load_map_value<'a,V: MemBufferDeserialize<'a,V> + MemBufferSerialize>(value_type: i32, big_endian: bool, val: Option<&'a [u8]>) -> Result<Option<V>,MemBufferError> {
+    if value_type != V::get_mem_buffer_type() {
+        return Err(MemBufferError::FieldTypeError(value_type,V::get_mem_buffer_type()));
+    }
+    match val {
+        Some(val) if big_endian => Ok(Some(V::from_mem_buffer::<byteorder::BigEndian>(val)?)),
+        Some(val) => Ok(Some(V::from_mem_buffer::<LittleEndian>(val)?)),
+        None => Ok(None)
+    }
+}
+
+//Layout of sorted set entries:
+//  the number of keys as u64 followed by the keys as u64 for sets of numbers
+//  the layout of a list entry for sets of byte strings
+//Layout of sorted map entries:
+//  the type id of the values as i32, four bytes of padding and the length of the keys as u64
+//  the keys in the layout of a sorted set, padded to a multiple of eight bytes
+//  start and end of the value of every key as u64 relative to the start of the data
+//  the values, every value is aligned to the alignment of its type relative to the start of the
+//  entry
+//Keys are sorted in ascending order without duplicates, byte strings are compared
+//lexicographically. All numbers are stored in the byte order of the buffer.
+
+const SORTED_MAP_HEADER_SIZE: usize = 16;
+
+///Sorted keys of a sorted set or map, implemented by `U64Keys` for numbers and by `BytesList`
+///for byte strings. Keys are located in O(1) and checked when they are accessed.
+pub trait SortedKeys<'a>: Copy {
+    type Key: Ord + Copy;
+
+    #[doc(hidden)]
+    fn set_type() -> i32;
+
+    #[doc(hidden)]
+    fn map_type() -> i32;
+
+    #[doc(hidden)]
+    fn from_keys<B: ByteOrder>(mem: &'a [u8]) -> Result<Self,MemBufferError>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn key(&self, index: usize) -> Result<Self::Key,MemBufferError>;
+}
+
+///Zero copy view on the sorted numbers of a sorted set or map
+#[derive(Clone,Copy,Debug)]
+pub struct U64Keys<'a> {
+    keys: &'a [u8],
+    big_endian: bool,
+}
+
+impl<'a> U64Keys<'a> {
+    ///Borrows the keys as slice, fails if they are not stored in the byte order of this machine
+    pub fn as_slice(&self) -> Result<&'a [u64],MemBufferError> {
+        if self.big_endian != cfg!(target_endian = "big") {
+            return Err(MemBufferError::WrongByteOrder { big_endian: self.big_endian });
+        }
+        //Safe as any bit pattern is a valid u64
+        unsafe { cast_native_slice(self.keys) }
+    }
+}
+
+impl<'a> SortedKeys<'a> for U64Keys<'a> {
+    type Key = u64;
+
+    fn set_type() -> i32 {
+        MemBufferTypes::SortedSetU64.into()
+    }
+
+    fn map_type() -> i32 {
+        MemBufferTypes::SortedMapU64.into()
+    }
+
+    fn from_keys<B: ByteOrder>(mem: &'a [u8]) -> Result<U64Keys<'a>,MemBufferError> {
+        if mem.len() < LIST_OFFSET_SIZE {
+            return Err(MemBufferError::InvalidLength { len: mem.len(), element_size: LIST_OFFSET_SIZE });
+        }
+        let count = B::read_u64(mem);
+        let keys = &mem[LIST_OFFSET_SIZE..];
+        if Some(keys.len()) != usize::try_from(count).ok().and_then(|x| x.checked_mul(LIST_OFFSET_SIZE)) {
+            return Err(MemBufferError::TruncatedList { count, len: mem.len() });
+        }
+        Ok(U64Keys { keys, big_endian: is_big_endian::<B>() })
+    }
+
+    fn len(&self) -> usize {
+        self.keys.len()/LIST_OFFSET_SIZE
+    }
+
+    fn key(&self, index: usize) -> Result<u64,MemBufferError> {
+        if index >= self.len() {
+            return Err(MemBufferError::KeyOutOfRange { key: index, len: self.len() });
+        }
+        let raw = &self.keys[index*LIST_OFFSET_SIZE..];
+        Ok(if self.big_endian { byteorder::BigEndian::read_u64(raw) } else { LittleEndian::read_u64(raw) })
+    }
+}
+
+impl<'a> SortedKeys<'a> for BytesList<'a> {
+    type Key = &'a [u8];
+
+    fn set_type() -> i32 {
+        MemBufferTypes::SortedSetBytes.into()
+    }
+
+    fn map_type() -> i32 {
+        MemBufferTypes::SortedMapBytes.into()
+    }
+
+    fn from_keys<B: ByteOrder>(mem: &'a [u8]) -> Result<BytesList<'a>,MemBufferError> {
+        BytesList::from_mem_buffer_with::<B>(mem)
+    }
+
+    fn len(&self) -> usize {
+        BytesList::len(self)
+    }
+
+    fn key(&self, index: usize) -> Result<&'a [u8],MemBufferError> {
+        self.get(index)
+    }
+}
+
+///Returns the index of the first key which is not smaller than the query, or which is larger
+///than the query if inclusive is false
+fn sorted_bound<'a,C: SortedKeys<'a>,Q>(keys: &C, query: &Q, inclusive: bool) -> Result<usize,MemBufferError> where C::Key: PartialOrd<Q> {
+    let (mut low, mut high) = (0, keys.len());
+    while low < high {
+        let mid = low + (high-low)/2;
+        let key = keys.key(mid)?;
+        if key < *query || (!inclusive && key == *query) {
+            low = mid+1;
+        } else {
+            high = mid;
+        }
+    }
+    Ok(low)
+}
+
+///Returns the indexes of all keys inside of the range
+fn sorted_range<'a,C: SortedKeys<'a>,Q,R: std::ops::RangeBounds<Q>>(keys: &C, range: &R) -> Result<std::ops::Range<usize>,MemBufferError> where C::Key: PartialOrd<Q> {
+    use std::ops::Bound;
+    let start = match range.start_bound() {
+        Bound::Included(x) => sorted_bound(keys, x, true)?,
+        Bound::Excluded(x) => sorted_bound(keys, x, false)?,
+        Bound::Unbounded => 0
+    };
+    let end = match range.end_bound() {
+        Bound::Included(x) => sorted_bound(keys, x, false)?,
+        Bound::Excluded(x) => sorted_bound(keys, x, true)?,
+        Bound::Unbounded => keys.len()
+    };
+    Ok(start..end.max(start))
+}
+
+///Zero copy view on a sorted set entry, queries use a binary search and only read the keys
+///they compare against. Keys are given by value, e. g. `set.contains(5)` for sets of numbers
+///and `set.contains(&b"key"[..])` for sets of byte strings.
+///```rust
+///use membuffer::{MemBufferWriter,MemBufferReader,SortedU64Set};
+///
+///let mut writer = MemBufferWriter::new();
+///writer.add_sorted_set_u64(vec![30,10,20,10]);
+///let data = writer.finalize().unwrap();
+///
+///let reader = MemBufferReader::new(&data).unwrap();
+///let set: SortedU64Set = reader.load_sorted_set(0).unwrap();
+///assert!(set.contains(20).unwrap());
+///assert_eq!(set.rank(25).unwrap(), 2);
+///assert_eq!(set.range(15..).unwrap().collect::<Result<Vec<_>,_>>().unwrap(), vec![20,30]);
+///```
+#[derive(Clone,Copy,Debug)]
+pub struct SortedSet<C> {
+    keys: C,
+}
+
+pub type SortedU64Set<'a> = SortedSet<U64Keys<'a>>;
+pub type SortedBytesSet<'a> = SortedSet<BytesList<'a>>;
+
+impl<'a,C: SortedKeys<'a>> SortedSet<C> {
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn keys(&self) -> &C {
+        &self.keys
+    }
+
+    ///Returns the key with the given index in ascending order
+    pub fn get(&self, index: usize) -> Result<C::Key,MemBufferError> {
+        self.keys.key(index)
+    }
+
+    ///Returns the number of keys which are smaller than the query
+    pub fn rank<Q>(&self, query: Q) -> Result<usize,MemBufferError> where C::Key: PartialOrd<Q> {
+        sorted_bound(&self.keys, &query, true)
+    }
+
+    pub fn contains<Q>(&self, query: Q) -> Result<bool,MemBufferError> where C::Key: PartialOrd<Q> {
+        let index = sorted_bound(&self.keys, &query, true)?;
+        Ok(index < self.len() && self.keys.key(index)? == query)
+    }
+
+    ///Iterates over all keys inside of the range in ascending order
+    pub fn range<Q,R: std::ops::RangeBounds<Q>>(&self, range: R) -> Result<impl Iterator<Item=Result<C::Key,MemBufferError>> + '_,MemBufferError> where C::Key: PartialOrd<Q> {
+        let indexes = sorted_range(&self.keys, &range)?;
+        Ok(indexes.map(move |index| self.keys.key(index)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=Result<C::Key,MemBufferError>> + '_ {
+        (0..self.len()).map(move |index| self.keys.key(index))
+    }
+}
+
+impl<'a,C: SortedKeys<'a>> MemBufferDeserialize<'a,SortedSet<C>> for SortedSet<C> {
+    fn from_mem_buffer<B: ByteOrder>(mem: &'a [u8]) -> Result<SortedSet<C>,MemBufferError> {
+        Ok(SortedSet { keys: C::from_keys::<B>(mem)? })
+    }
+}
+
+///Zero copy view on a sorted map entry, see `SortedSet` for the queries on the keys. Values are
+///only read for the keys which are returned.
+///```rust
+///use membuffer::{MemBufferWriter,MemBufferReader,SortedBytesMap};
+///
+///let mut writer = MemBufferWriter::new();
+///writer.add_sorted_map_bytes(vec![("b","second"),("a","first"),("c","third")]).unwrap();
+///let data = writer.finalize().unwrap();
+///
+///let reader = MemBufferReader::new(&data).unwrap();
+///let map: SortedBytesMap = reader.load_sorted_map(0).unwrap();
+///assert_eq!(map.get_as::<&str,_>(&b"b"[..]).unwrap(), Some("second"));
+///let keys: Vec<&[u8]> = map.range(&b"b"[..]..).unwrap().map(|x| x.unwrap().0).collect();
+///assert_eq!(keys, vec![&b"b"[..], &b"c"[..]]);
+///```
+#[derive(Clone,Copy,Debug)]
+pub struct SortedMap<'a,C> {
+    keys: C,
+    ranges: &'a [u8],
+    data: &'a [u8],
+    value_type: i32,
+    big_endian: bool,
+}
+
+pub type SortedU64Map<'a> = SortedMap<'a,U64Keys<'a>>;
+pub type SortedBytesMap<'a> = SortedMap<'a,BytesList<'a>>;
+
+///Result of iterating over the pairs of a sorted map
+pub type SortedPair<'a,C> = Result<(<C as SortedKeys<'a>>::Key,&'a [u8]),MemBufferError>;
+
+impl<'a,C: SortedKeys<'a>> SortedMap<'a,C> {
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    ///The type id all values of the map are stored with
+    pub fn value_type(&self) -> i32 {
+        self.value_type
+    }
+
+    ///The keys of the map as sorted set
+    pub fn keys(&self) -> SortedSet<C> {
+        SortedSet { keys: self.keys }
+    }
+
+    ///Returns the raw value of the key with the given index in ascending order
+    pub fn value(&self, index: usize) -> Result<&'a [u8],MemBufferError> {
+        if index >= self.len() {
+            return Err(MemBufferError::KeyOutOfRange { key: index, len: self.len() });
+        }
+        let raw = &self.ranges[index*2*LIST_OFFSET_SIZE..];
+        let read = |raw: &[u8]| if self.big_endian { byteorder::BigEndian::read_u64(raw) } else { LittleEndian::read_u64(raw) };
+        let (start,end) = (read(raw),read(&raw[LIST_OFFSET_SIZE..]));
+        if start > end || end > self.data.len() as u64 {
+            return Err(MemBufferError::InvalidPosition { key: index, start: start as i64, end: end as i64, payload_len: self.data.len() });
+        }
+        Ok(&self.data[start as usize..end as usize])
+    }
+
+    ///Returns the raw value stored for the key
+    pub fn get<Q>(&self, query: Q) -> Result<Option<&'a [u8]>,MemBufferError> where C::Key: PartialOrd<Q> {
+        let index = sorted_bound(&self.keys, &query, true)?;
+        if index < self.len() && self.keys.key(index)? == query {
+            return Ok(Some(self.value(index)?));
+        }
+        Ok(None)
+    }
+
+    ///Loads the value stored for the key as V, fails with `FieldTypeError` if the values of the
+    ///map have another type
+    pub fn get_as<V: MemBufferDeserialize<'a,V> + MemBufferSerialize,Q>(&self, query: Q) -> Result<Option<V>,MemBufferError> where C::Key: PartialOrd<Q> {
+        load_map_value(self.value_type, self.big_endian, self.get(query)?)
+    }
+
+    ///Iterates over all keys inside of the range and their raw values in ascending order
+    pub fn range<Q,R: std::ops::RangeBounds<Q>>(&self, range: R) -> Result<impl Iterator<Item=SortedPair<'a,C>> + '_,MemBufferError> where C::Key: PartialOrd<Q> {
+        let indexes = sorted_range(&self.keys, &range)?;
+        Ok(indexes.map(move |index| Ok((self.keys.key(index)?,self.value(index)?))))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=SortedPair<'a,C>> + '_ {
+        (0..self.len()).map(move |index| Ok((self.keys.key(index)?,self.value(index)?)))
+    }
+}
+
+impl<'a,C: SortedKeys<'a>> MemBufferDeserialize<'a,SortedMap<'a,C>> for SortedMap<'a,C> {
+    fn from_mem_buffer<B: ByteOrder>(mem: &'a [u8]) -> Result<SortedMap<'a,C>,MemBufferError> {
+        if mem.len() < SORTED_MAP_HEADER_SIZE {
+            return Err(MemBufferError::InvalidLength { len: mem.len(), element_size: SORTED_MAP_HEADER_SIZE });
+        }
+        let keys_len = B::read_u64(&mem[8..]);
+        let keys_end = usize::try_from(keys_len).ok()
+            .and_then(|x| x.checked_add(SORTED_MAP_HEADER_SIZE))
+            .filter(|x| *x <= mem.len())
+            .ok_or(MemBufferError::TruncatedList { count: keys_len, len: mem.len() })?;
+        let keys = C::from_keys::<B>(&mem[SORTED_MAP_HEADER_SIZE..keys_end])?;
+        let ranges_start = keys_end.div_ceil(LIST_OFFSET_SIZE)*LIST_OFFSET_SIZE;
+        let ranges_end = keys.len().checked_mul(2*LIST_OFFSET_SIZE).and_then(|x| x.checked_add(ranges_start));
+        match ranges_end {
+            Some(ranges_end) if ranges_end <= mem.len() => Ok(SortedMap {
+                keys,
+                ranges: &mem[ranges_start..ranges_end],
+                data: &mem[ranges_end..],
+                value_type: B::read_i32(mem),
+                big_endian: is_big_endian::<B>(),
+            }),
+            _ => Err(MemBufferError::TruncatedList { count: keys.len() as u64, len: mem.len() })
+        }
+    }
+}
+
+///Writes sorted numbers in the layout of a sorted set
+fn u64_keys_to_bytes<B: ByteOrder>(keys: &[u64]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(LIST_OFFSET_SIZE*(keys.len()+1));
+    buf.write_u64::<B>(keys.len() as u64).unwrap();
+    for x in keys {
+        buf.write_u64::<B>(*x).unwrap();
+    }
+    buf
+}
+
+//Absent values are marked with the Null type in the header of the entry
+impl<'a,T: MemBufferDeserialize<'a,T>> MemBufferDeserialize<'a,Option<T>> for Option<T> {
+    fn from_mem_buffer<B: ByteOrder>(mem: &'a [u8]) -> Result<Option<T>,MemBufferError> {
+        Ok(Some(T::from_mem_buffer::<B>(mem)?))
+    }
+
+    fn from_mem_buffer_null(_: i32) -> Result<Option<T>,MemBufferError> {
+        Ok(None)
+    }
+}
+
+impl<'a,R: ByteOrder> MemBufferDeserialize<'a,MemBufferReader<'a,R>> for MemBufferReader<'a,R> {
+    fn from_mem_buffer<B: ByteOrder>(mem: &'a [u8]) -> Result<MemBufferReader<'a,R>,MemBufferError> {
+        let reader = MemBufferReader::with_byte_order(mem)?;
+        Ok(reader)
+    }
+}
+
+///Typed handle of an entry, binds the index of the entry to the type it is stored with so the
+///type does not have to be repeated on every load. Handles are usually declared as constants,
+///fields of borrowed types use the `'static` lifetime and are loaded with the lifetime of the
+///reader:
+///```rust
+///use membuffer::{MemBufferWriter,MemBufferReader,Field};
+///
+///const TITLE: Field<&str> = Field::new(0);
+///const VIEWS: Field<u64> = Field::new(1);
+///
+///let mut writer = MemBufferWriter::new();
+///writer.put(VIEWS, 1000);
+///writer.put(TITLE, "Membuffer");
+///let data = writer.finalize().unwrap();
+///
+///let reader = MemBufferReader::new(&data).unwrap();
+///assert_eq!(reader.get(TITLE).unwrap(), "Membuffer");
+///assert_eq!(reader.get(VIEWS).unwrap(), 1000);
+///```
+///Handles of the fields of a `Schema` name the schema as second parameter, they are loaded
+///through a `SchemaReader` of that schema without checking their type on every load.
+pub struct Field<T,S = ()> {
+    index: usize,
+    //Covariant in T so a Field<&'static str> can be used to load a &str of any lifetime
+    value_type: PhantomData<fn() -> T>,
+    schema: PhantomData<fn() -> S>,
+}
+
+impl<T,S> Field<T,S> {
+    pub const fn new(index: usize) -> Field<T,S> {
+        Field { index, value_type: PhantomData, schema: PhantomData }
+    }
+
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<T: MemBufferSerialize,S> Field<T,S> {
+    ///Describes the field for the `Schema` it belongs to
+    pub fn info(&self) -> FieldInfo {
+        FieldInfo {
+            index: self.index,
+            type_id: T::get_mem_buffer_type(),
+            nullable: T::is_mem_buffer_nullable(),
+            optional: T::is_mem_buffer_nullable(),
+        }
+    }
+}
+
+impl<T,S> Clone for Field<T,S> {
+    fn clone(&self) -> Field<T,S> {
+        *self
+    }
+}
+
+impl<T,S> Copy for Field<T,S> {}
+
+impl<T,S> std::fmt::Debug for Field<T,S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f,"Field({})",self.index)
+    }
+}
+
+///Index and type of a field of a `Schema`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldInfo {
+    pub index: usize,
+    pub type_id: i32,
+    ///True if the entry may be stored as null, e. g. for fields of Option types
+    pub nullable: bool,
+    ///True if buffers may end before the entry, e. g. as they were written before the field was
+    ///added. Fields of Option types are optional by default.
+    pub optional: bool,
+}
+
+impl FieldInfo {
+    ///Describes a retired slot, buffers written after the field was removed store a tombstone at
+    ///the index while older buffers may still store the value of the field
+    pub fn retired(index: usize) -> FieldInfo {
+        FieldInfo {
+            index,
+            type_id: MemBufferTypes::Tombstone.into(),
+            nullable: true,
+            optional: true,
+        }
+    }
+
+    ///Marks the field as optional, use this for fields which were added to a schema later on and
+    ///are loaded with `MemBufferReader::get_or_default`
+    pub fn optional(self) -> FieldInfo {
+        FieldInfo { optional: true, ..self }
+    }
+
+    pub fn is_retired(&self) -> bool {
+        self.type_id == MemBufferTypes::Tombstone as i32
+    }
+}
+
+///Lists all fields of a buffer layout, a reader checks the type of every field once with
+///`MemBufferReader::with_schema` so missing or mistyped fields are reported when the buffer is
+///opened instead of on the first load. Every handle `Field<T,S>` of the schema S has to be
+///described by `fields` as the `SchemaReader` trusts the check done when it was created, a handle
+///which is not described loads whatever the entry holds or fails to decode it.
+///```rust
+///use membuffer::{MemBufferWriter,MemBufferReader,Field,FieldInfo,Schema};
+///
+///struct Article;
+///
+///impl Article {
+///    const TITLE: Field<&'static str,Article> = Field::new(0);
+///    const TAGS: Field<Option<&'static [u32]>,Article> = Field::new(1);
+///}
+///
+///impl Schema for Article {
+///    fn fields() -> Vec<FieldInfo> {
+///        vec![Article::TITLE.info(), Article::TAGS.info()]
+///    }
+///}
+///
+///let mut writer = MemBufferWriter::new();
+///writer.put(Article::TITLE, "Membuffer");
+///writer.put(Article::TAGS, None);
+///let data = writer.finalize().unwrap();
+///
+///let reader = MemBufferReader::new(&data).unwrap().with_schema::<Article>().unwrap();
+///assert_eq!(reader.get(Article::TITLE).unwrap(), "Membuffer");
+///assert_eq!(reader.get(Article::TAGS).unwrap(), None);
+///```
+pub trait Schema {
+    fn fields() -> Vec<FieldInfo>;
+}
+
+///A change between two versions of a schema which breaks readers of one version when they read
+///buffers written with the other version, see `check_compatibility`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakingChange {
+    ///The field at the index is stored with another type
+    TypeChanged { index: usize, old: i32, new: i32 },
+    ///The field at the index became nullable or stopped being nullable
+    NullableChanged { index: usize },
+    ///The field at the index was removed without retiring its slot, new fields could reuse it
+    FieldRemoved { index: usize },
+    ///The retired slot at the index is used by a field again
+    SlotReused { index: usize },
+    ///The field at the index was added but is not optional, buffers of the old version end
+    ///before it
+    RequiredFieldAdded { index: usize },
+    ///The optional field at the index became required, buffers which end before it cannot be
+    ///read anymore
+    FieldBecameRequired { index: usize },
+    ///The schema describes the index more than once
+    DuplicateIndex { index: usize },
+}
+
+///Finds all breaking changes between the old and the new version of a schema. Schemas evolve
+///compatibly if they follow these rules:
+///- Fields keep their index and type forever, a nullable field stays nullable and vice versa
+///- New fields are optional, see `FieldInfo::optional`, and are usually added at the end. Readers
+///  load them as None or default from older buffers which end before them
+///- Removed fields leave a tombstone at their index, see `FieldInfo::retired` and
+///  `MemBufferWriter::add_tombstone`, which is never used by another field again. Older readers
+///  load a tombstone as None if the field was nullable and fail with `RetiredEntry` otherwise
+///```rust
+///use membuffer::{check_compatibility,BreakingChange,Field,FieldInfo};
+///
+///const TITLE: Field<&str> = Field::new(0);
+///const VIEWS: Field<u64> = Field::new(1);
+///const TAGS: Field<Option<&[u32]>> = Field::new(2);
+///
+///let old = vec![TITLE.info(), VIEWS.info()];
+///let new = vec![TITLE.info(), FieldInfo::retired(1), TAGS.info()];
+///assert_eq!(check_compatibility(&old, &new), Ok(()));
+///
+///let new = vec![TITLE.info(), Field::<u32>::new(1).info()];
+///assert_eq!(check_compatibility(&old, &new), Err(vec![BreakingChange::TypeChanged { index: 1, old: VIEWS.info().type_id, new: Field::<u32>::new(1).info().type_id }]));
+///```
+pub fn check_compatibility(old: &[FieldInfo], new: &[FieldInfo]) -> Result<(),Vec<BreakingChange>> {
+    let mut changes = Vec::new();
+    for schema in [old,new].iter() {
+        for (pos,field) in schema.iter().enumerate() {
+            if schema[..pos].iter().any(|x| x.index == field.index) {
+                changes.push(BreakingChange::DuplicateIndex { index: field.index });
+            }
+        }
+    }
+    for field in new {
+        let index = field.index;
+        match old.iter().find(|x| x.index == index) {
+            None if !field.optional && !field.is_retired() => changes.push(BreakingChange::RequiredFieldAdded { index }),
+            None => {}
+            Some(prev) if prev.is_retired() && !field.is_retired() => changes.push(BreakingChange::SlotReused { index }),
+            Some(prev) if prev.is_retired() || field.is_retired() => {}
+            Some(prev) => {
+                if prev.type_id != field.type_id {
+                    changes.push(BreakingChange::TypeChanged { index, old: prev.type_id, new: field.type_id });
+                }
+                if prev.nullable != field.nullable {
+                    changes.push(BreakingChange::NullableChanged { index });
+                }
+                if prev.optional && !field.optional {
+                    changes.push(BreakingChange::FieldBecameRequired { index });
+                }
+            }
+        }
+    }
+    for prev in old {
+        if !new.iter().any(|x| x.index == prev.index) {
+            changes.push(BreakingChange::FieldRemoved { index: prev.index });
+        }
+    }
+    if changes.is_empty() {
+        return Ok(());
+    }
+    Err(changes)
+}
+
+///Reader whose layout was checked against the schema S, see `MemBufferReader::with_schema`. It
+///only loads the fields of S, handles of other schemas are rejected at compile time:
+///```compile_fail
+///use membuffer::{MemBufferWriter,MemBufferReader,Field,FieldInfo,Schema};
+///
+///struct Article;
+///struct Comment;
+///
+///impl Schema for Article {
+///    fn fields() -> Vec<FieldInfo> {
+///        vec![Field::<&str,Article>::new(0).info()]
+///    }
+///}
+///
+///const TEXT: Field<u64,Comment> = Field::new(0);
+///
+///let mut writer = MemBufferWriter::new();
+///writer.add_entry("Membuffer");
+///let data = writer.finalize().unwrap();
+///let reader = MemBufferReader::new(&data).unwrap().with_schema::<Article>().unwrap();
+///reader.get(TEXT);
+///```
+pub struct SchemaReader<'a,S,B: ByteOrder = LittleEndian> {
+    reader: MemBufferReader<'a,B>,
+    schema: PhantomData<S>,
+}
+
+impl<'a,S: Schema,B: ByteOrder> SchemaReader<'a,S,B> {
+    ///Loads the field without comparing the type of the entry again, the type was checked when
+    ///the reader was created
+    pub fn get<T: MemBufferDeserialize<'a,T> + MemBufferSerialize>(&self, field: Field<T,S>) -> Result<T,MemBufferError> {
+        self.reader.load_checked_entry(field.index(), T::get_mem_buffer_type())
+    }
+
+    ///Loads the field like `get`, returns the default value of its type if the entry is missing
+    ///or was stored as null or tombstone
+    pub fn get_or_default<T: MemBufferDeserialize<'a,T> + MemBufferSerialize + Default>(&self, field: Field<T,S>) -> Result<T,MemBufferError> {
+        if self.reader.is_absent(field.index) {
+            return Ok(T::default());
+        }
+        self.get(field)
+    }
+
+    ///The underlying reader to load entries which are not part of the schema
+    pub fn reader(&self) -> &MemBufferReader<'a,B> {
+        &self.reader
+    }
+}
+
+///Types which can be stored in a struct deriving `MemBuffer`, maps the type of the field to the
+///type its accessor on the view of the struct returns, e. g. `String` to `&str`. Structs
+///deriving `MemBuffer` implement this as well to be stored as nested buffer. All derived buffers
+///are written in little endian byte order.
+pub trait MemBufferView<'a> {
+    type View;
+
+    ///Adds the value as the next entry of the writer
+    fn add_to(&self, writer: &mut MemBufferWriter) -> Result<(),MemBufferError>;
+
+    ///Loads the value of the entry with the given key
+    fn load_from(reader: &MemBufferReader<'a>, key: usize) -> Result<Self::View,MemBufferError>;
+}
+
+macro_rules! impl_mem_buffer_view {
+    ($t:ty, $view:ty, |$val:ident| $add:expr) => {
+        impl<'a> MemBufferView<'a> for $t {
+            type View = $view;
+
+            fn add_to(&self, writer: &mut MemBufferWriter) -> Result<(),MemBufferError> {
+                let $val = self;
+                writer.add_entry($add);
+                Ok(())
+            }
+
+            fn load_from(reader: &MemBufferReader<'a>, key: usize) -> Result<$view,MemBufferError> {
+                reader.load_entry(key)
+            }
+        }
+    };
+}
+
+impl_mem_buffer_view!(u8, u8, |x| *x);
+impl_mem_buffer_view!(u16, u16, |x| *x);
+impl_mem_buffer_view!(u32, u32, |x| *x);
+impl_mem_buffer_view!(u64, u64, |x| *x);
+impl_mem_buffer_view!(u128, u128, |x| *x);
+impl_mem_buffer_view!(i8, i8, |x| *x);
+impl_mem_buffer_view!(i16, i16, |x| *x);
+impl_mem_buffer_view!(i32, i32, |x| *x);
+impl_mem_buffer_view!(i64, i64, |x| *x);
+impl_mem_buffer_view!(i128, i128, |x| *x);
+impl_mem_buffer_view!(f32, f32, |x| *x);
+impl_mem_buffer_view!(f64, f64, |x| *x);
+impl_mem_buffer_view!(bool, bool, |x| *x);
+impl_mem_buffer_view!(char, char, |x| *x);
+impl_mem_buffer_view!(String, &'a str, |x| x.as_str());
+impl_mem_buffer_view!(Vec<u8>, &'a [u8], |x| &x[..]);
+impl_mem_buffer_view!(Vec<u16>, Cow<'a,[u16]>, |x| &x[..]);
+impl_mem_buffer_view!(Vec<u32>, Cow<'a,[u32]>, |x| &x[..]);
+impl_mem_buffer_view!(Vec<u64>, Cow<'a,[u64]>, |x| &x[..]);
+impl_mem_buffer_view!(Vec<u128>, Cow<'a,[u128]>, |x| &x[..]);
+impl_mem_buffer_view!(Vec<i8>, Cow<'a,[i8]>, |x| &x[..]);
+impl_mem_buffer_view!(Vec<i16>, Cow<'a,[i16]>, |x| &x[..]);
+impl_mem_buffer_view!(Vec<i32>, Cow<'a,[i32]>, |x| &x[..]);
+impl_mem_buffer_view!(Vec<i64>, Cow<'a,[i64]>, |x| &x[..]);
+impl_mem_buffer_view!(Vec<i128>, Cow<'a,[i128]>, |x| &x[..]);
+impl_mem_buffer_view!(Vec<f32>, Cow<'a,[f32]>, |x| &x[..]);
+impl_mem_buffer_view!(Vec<f64>, Cow<'a,[f64]>, |x| &x[..]);
+impl_mem_buffer_view!(Vec<String>, StrList<'a>, |x| &x[..]);
+impl_mem_buffer_view!(Vec<Vec<u8>>, BytesList<'a>, |x| &x[..]);
+
+impl<'a,T: MemBufferView<'a>> MemBufferView<'a> for Option<T> {
+    type View = Option<T::View>;
+
+    fn add_to(&self, writer: &mut MemBufferWriter) -> Result<(),MemBufferError> {
+        match self {
+            Some(val) => val.add_to(writer),
+            None => {
+                writer.add_entry(None::<&str>);
+                Ok(())
+            }
+        }
+    }
+
+    fn load_from(reader: &MemBufferReader<'a>, key: usize) -> Result<Option<T::View>,MemBufferError> {
+        if reader.is_absent(key) {
+            return Ok(None);
+        }
+        Ok(Some(T::load_from(reader, key)?))
+    }
+}
+
+///Description of an entry in the schema block of a buffer, see `BufferSchema`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntrySchema<'a> {
+    ///The name of the entry if it was added as named entry
+    pub name: Option<&'a str>,
+    pub type_id: i32,
+    ///The name of a built-in type or of a type registered with `MemBufferWriter::register_type`
+    pub type_name: Option<&'a str>,
+    ///The name of the type of the elements of vectors, lists and sets and of the values of maps
+    pub element_type: Option<&'a str>,
+}
+
+///Self-describing schema stored in a buffer written with `MemBufferWriter::set_schema`. The
+///schema block is a buffer on its own which is stored in the header and holds one column per
+///property of the entries:
+///  0: names of all entries as text list, empty for unnamed entries
+///  1: type ids of all entries as [i32]
+///  2: type names of all entries as text list, empty for unknown types
+///  3: element type names of all entries as text list, empty if the type has no elements
+///  4: schema blocks of the nested buffers of all entries as bytes list, empty for entries which
+///     are no nested buffer or for nested buffers without schema
+///  5: type ids registered with `MemBufferWriter::register_type` as [i32]
+///  6: names of the registered types as text list
+///All numbers are stored in the byte order of the buffer holding the schema.
+///```rust
+///use membuffer::{MemBufferWriter,MemBufferReader};
+///
+///let mut writer = MemBufferWriter::new();
+///writer.set_schema(true);
+///writer.add_named_entry("title", "Membuffer").unwrap();
+///writer.add_entry(&[1u32,2,3][..]);
+///writer.register_type(100, "Thumbnail");
+///let data = writer.finalize().unwrap();
+///
+///let reader = MemBufferReader::new(&data).unwrap();
+///let schema = reader.schema().unwrap().unwrap();
+///let title = schema.entry(0).unwrap();
+///assert_eq!(title.name, Some("title"));
+///assert_eq!(title.type_name, Some("text"));
+///assert_eq!(schema.entry(1).unwrap().element_type, Some("u32"));
+///assert_eq!(schema.type_name(100).unwrap(), Some("Thumbnail"));
+///```
+pub struct BufferSchema<'a,B: ByteOrder = LittleEndian> {
+    names: StrList<'a>,
+    type_ids: Cow<'a,[i32]>,
+    type_names: StrList<'a>,
+    element_types: StrList<'a>,
+    nested: BytesList<'a>,
+    registry_ids: Cow<'a,[i32]>,
+    registry_names: StrList<'a>,
+    byte_order: PhantomData<B>,
+}
+
+///Returns None for empty texts
+fn non_empty(val: &str) -> Option<&str> {
+    if val.is_empty() { None } else { Some(val) }
+}
+
+impl<'a,B: ByteOrder> BufferSchema<'a,B> {
+    ///Reads the schema block of a buffer
+    pub fn from_slice(mem: &'a [u8]) -> Result<BufferSchema<'a,B>,MemBufferError> {
+        let reader = MemBufferReader::<B>::with_byte_order(mem)?;
+        let schema = BufferSchema {
+            names: reader.load_entry(0)?,
+            type_ids: reader.load_entry(1)?,
+            type_names: reader.load_entry(2)?,
+            element_types: reader.load_entry(3)?,
+            nested: reader.load_entry(4)?,
+            registry_ids: reader.load_entry(5)?,
+            registry_names: reader.load_entry(6)?,
+            byte_order: PhantomData,
+        };
+        let len = schema.type_ids.len();
+        if schema.names.len() != len || schema.type_names.len() != len || schema.element_types.len() != len
+            || schema.nested.len() != len || schema.registry_names.len() != schema.registry_ids.len() {
+            return Err(MemBufferError::WrongFormat);
+        }
+        Ok(schema)
+    }
+
+    ///The number of entries described by the schema
+    pub fn len(&self) -> usize {
+        self.type_ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.type_ids.is_empty()
+    }
+
+    pub fn entry(&self, index: usize) -> Result<EntrySchema<'a>,MemBufferError> {
+        if index >= self.len() {
+            return Err(MemBufferError::KeyOutOfRange { key: index, len: self.len() });
+        }
+        Ok(EntrySchema {
+            name: non_empty(self.names.get(index)?),
+            type_id: self.type_ids[index],
+            type_name: non_empty(self.type_names.get(index)?),
+            element_type: non_empty(self.element_types.get(index)?),
+        })
+    }
+
+    ///Returns the schema of the nested buffer stored in the entry, None if the entry is no nested
+    ///buffer or the nested buffer was written without schema
+    pub fn nested(&self, index: usize) -> Result<Option<BufferSchema<'a,B>>,MemBufferError> {
+        let nested = self.nested.get(index)?;
+        if nested.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(BufferSchema::from_slice(nested)?))
+    }
+
+    ///Returns the name of a registered or built-in type
+    pub fn type_name(&self, type_id: i32) -> Result<Option<&'a str>,MemBufferError> {
+        match self.registry_ids.iter().position(|x| *x == type_id) {
+            Some(index) => Ok(Some(self.registry_names.get(index)?)),
+            None => Ok(builtin_type_name(type_id))
+        }
+    }
+
+    ///Iterates over the ids and names of all types registered by the writer
+    pub fn registry(&self) -> impl Iterator<Item=Result<(i32,&'a str),MemBufferError>> + '_ {
+        self.registry_ids.iter().enumerate().map(move |(index,id)| Ok((*id,self.registry_names.get(index)?)))
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item=Result<EntrySchema<'a>,MemBufferError>> + '_ {
+        (0..self.len()).map(move |index| self.entry(index))
+    }
+}
+
+impl<B: ByteOrder> std::fmt::Debug for BufferSchema<'_,B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_list().entries(self.entries()).finish()
+    }
+}
+
+///The reader which is used for reading the memory area produced by the writer. The byte order
+///of the buffer is stored in its header, buffers are written in little endian by default which
+///is the byte order this reader expects. Use `MemBufferReader::<BigEndian>::with_byte_order` to
+///read buffers written in big endian, slices in a byte order foreign to the machine cannot be
+///borrowed but can be loaded as Cow copy.
+///```rust
+///use membuffer::{MemBufferWriter,MemBufferReader};
+///
+///let mut data = MemBufferWriter::new();
+///data.add_entry("Add some data to save to file or send over the network");
+///let data_vec = data.finalize().unwrap();
+/////The reader is type sensitive
+///let reader = MemBufferReader::new(&data_vec).unwrap();
+/////We load the first entry, try not to get this mixed up
+///assert_eq!(reader.load_entry::<&str>(0).unwrap(),"Add some data to save to file or send over the network");
+///```
+pub struct MemBufferReader<'a,B: ByteOrder = LittleEndian> {
+    version: u16,
+    flags: u32,
+    position_size: usize,
+    lazy_checksums: bool,
+    offsets: &'a [u8],
+    names: &'a [u8],
+    schema: &'a [u8],
+    data: &'a [u8],
+    byte_order: PhantomData<B>
+}
+
+impl<'a> MemBufferReader<'a> {
+    ///Deserialize data from a buffer to an i32 integer stored in the byte order of this machine
+    ///like the headers of the 0.3 format, buffers of the current format are little endian by
+    ///default
+    pub fn deserialize_i32_from(mut buffer: &[u8]) -> i32 {
+        buffer.read_i32::<NativeEndian>().unwrap()
+    }
+
+    ///Creates a new memory format reader from the given memory slice, as the readed values are
+    ///borrowed from the memory slice the reader cannot outlive the memory it borrows from. Every
+    ///header entry is validated against the payload so corrupted or hostile input results in an
+    ///error instead of a panic when loading entries. The checksum of an entry is verified every
+    ///time the entry is loaded, see `without_checksums`.
+    pub fn new(val: &'a [u8]) -> Result<MemBufferReader<'a>,MemBufferError> {
+        MemBufferReader::with_byte_order(val)
+    }
+
+    ///Creates a new memory format reader without validating the positions of the entries. The
+    ///header itself is still checked, this only skips the walk over all entries which is done in
+    ///`new`. Loading an entry from a corrupted buffer opened this way may panic, only use it for
+    ///buffers which were produced by a trusted writer.
+    pub fn new_unchecked(val: &'a [u8]) -> Result<MemBufferReader<'a>,MemBufferError> {
+        MemBufferReader::with_byte_order_unchecked(val)
+    }
+}
+
+impl<'a,B: ByteOrder> MemBufferReader<'a,B> {
+    pub fn len(&self) -> usize {
+        self.offsets.len()/self.position_size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    ///Reads the header entry of the given key, the header is read field by field as the memory
+    ///is not guaranteed to be aligned
+    fn position(&self, key: usize) -> InternPosition {
+        let raw = &self.offsets[key*self.position_size..(key+1)*self.position_size];
+        let (pos, raw) = if self.flags & FLAG_OFFSETS_64 != 0 {
+            (Position { start: B::read_i64(raw), end: B::read_i64(&raw[8..]) }, &raw[16..])
+        } else {
+            (Position { start: B::read_i32(raw) as i64, end: B::read_i32(&raw[4..]) as i64 }, &raw[8..])
+        };
+        InternPosition {
+            pos,
+            variable_type: B::read_i32(raw),
+            checksum: if self.has_checksums() { B::read_u32(&raw[4..]) } else { 0 },
+        }
+    }
+
+    pub fn payload_len(&self) -> usize {
+        self.data.len()
+    }
+
+    ///The format version of the buffer, `LEGACY_FORMAT_VERSION` for buffers without versioned
+    ///header
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    ///The flags stored in the header of the buffer, legacy buffers only report their byte order
+    pub fn flags(&self) -> u32 {
+        self.flags
+    }
+
+    fn has_checksums(&self) -> bool {
+        self.flags & FLAG_CHECKSUMS != 0
+    }
+
+    ///Enables verifying the checksum of an entry every time it is loaded, this is the default
+    ///and touches the whole payload of the entry on every load. Does nothing for buffers without
+    ///FLAG_CHECKSUMS.
+    pub fn with_lazy_checksums(mut self) -> MemBufferReader<'a,B> {
+        self.lazy_checksums = true;
+        self
+    }
+
+    ///Disables verifying the checksum of an entry when it is loaded, use this for buffers from a
+    ///trusted source with large entries which are loaded often. The checksum of the header is
+    ///still verified and `verify_checksums` still checks all entries.
+    pub fn without_checksums(mut self) -> MemBufferReader<'a,B> {
+        self.lazy_checksums = false;
+        self
+    }
+
+    ///Compares the checksum of the entry with the given key against its payload
+    fn verify_entry_checksum(&self, key: usize, entry: &InternPosition) -> Result<(),MemBufferError> {
+        let payload = &self.data[entry.pos.start as usize..entry.pos.end as usize];
+        if crc32c::crc32c(payload) != entry.checksum {
+            return Err(MemBufferError::ChecksumMismatch { key });
+        }
+        Ok(())
+    }
+
+    ///Verifies the checksums of all entries at once, use this to check the whole buffer eagerly
+    ///when opening it. Does nothing for buffers without FLAG_CHECKSUMS.
+    ///```rust
+    ///use membuffer::{MemBufferWriter,MemBufferReader,MemBufferError};
+    ///
+    ///let mut writer = MemBufferWriter::new();
+    ///writer.add_entry("Hello World");
+    ///let mut data = writer.finalize().unwrap();
+    ///
+    /////Flip a bit in the payload
+    ///let last = data.len()-1;
+    ///data[last] ^= 1;
+    ///
+    ///let reader = MemBufferReader::new(&data).unwrap();
+    ///assert!(matches!(reader.verify_checksums(), Err(MemBufferError::ChecksumMismatch { key: 0 })));
+    ///```
+    pub fn verify_checksums(&self) -> Result<(),MemBufferError> {
+        if !self.has_checksums() {
+            return Ok(());
+        }
+        for key in 0..self.len() {
+            self.verify_entry_checksum(key, &self.position(key))?;
+        }
+        Ok(())
+    }
+    
+    ///Internal load function this is needed to enable loading nested MemBufferWriters which does
+    ///not implement the Deserialize trait
+    fn intern_load_entry<X: MemBufferDeserialize<'a,X>>(&self, key: usize, expected_type: i32) -> Result<X,MemBufferError> {
+        if self.is_absent(key) && self.entry_type(key) != Some(expected_type) {
+            return self.load_absent(key, expected_type);
+        }
+        let entry = self.position(key);
+        let is_type = entry.variable_type;
+        if is_type != expected_type {
+            return Err(MemBufferError::FieldTypeError(is_type,expected_type));
+        }
+        self.load_payload(key, &entry)
+    }
+
+    ///Loads an entry whose type was checked by `check_schema` before, only absent entries are
+    ///told apart from stored ones
+    fn load_checked_entry<X: MemBufferDeserialize<'a,X>>(&self, key: usize, expected_type: i32) -> Result<X,MemBufferError> {
+        if self.is_absent(key) && self.entry_type(key) != Some(expected_type) {
+            return self.load_absent(key, expected_type);
+        }
+        self.load_payload(key, &self.position(key))
+    }
+
+    fn load_payload<X: MemBufferDeserialize<'a,X>>(&self, key: usize, entry: &InternPosition) -> Result<X,MemBufferError> {
+        if self.lazy_checksums && self.has_checksums() {
+            self.verify_entry_checksum(key, entry)?;
+        }
+        X::from_mem_buffer::<B>(&self.data[entry.pos.start as usize..entry.pos.end as usize])
+    }
+
+    ///Returns the type of the entry with the given key, None if the buffer ends before the key
+    fn entry_type(&self, key: usize) -> Option<i32> {
+        if key >= self.len() {
+            return None;
+        }
+        Some(self.position(key).variable_type)
+    }
+
+    ///Returns true if the entry has no value: the buffer ends before the key, e. g. as it was
+    ///written before the field was added, or the entry is a null entry or a tombstone
+    pub fn is_absent(&self, key: usize) -> bool {
+        match self.entry_type(key) {
+            Some(is_type) => is_type == MemBufferTypes::Null as i32 || is_type == MemBufferTypes::Tombstone as i32,
+            None => true
+        }
+    }
+
+    ///Loads an entry without value, only nullable types like Option can be loaded from these
+    fn load_absent<X: MemBufferDeserialize<'a,X>>(&self, key: usize, expected_type: i32) -> Result<X,MemBufferError> {
+        match self.entry_type(key) {
+            None => X::from_mem_buffer_null(expected_type).map_err(|_| MemBufferError::KeyOutOfRange { key, len: self.len() }),
+            Some(is_type) if is_type == MemBufferTypes::Tombstone as i32 => X::from_mem_buffer_null(expected_type).map_err(|_| MemBufferError::RetiredEntry { key }),
+            Some(_) => X::from_mem_buffer_null(expected_type)
+        }
+    }
+
+    ///Load one entry with the given type, expecting the serializable trait as well to determine
+    ///the integer type, when doing polymorphismus of structures use the same integer for multiple
+    ///types
+    pub fn load_entry<X: MemBufferDeserialize<'a,X> + MemBufferSerialize>(&self,key: usize) -> Result<X,MemBufferError> {
+        self.intern_load_entry(key, X::get_mem_buffer_type())
+    }
+
+    ///Loads the entry with the given type, returns the default value of the type if the buffer
+    ///ends before the key or the entry was stored as null or tombstone. Use this for fields which
+    ///were added after the buffer was written.
+    pub fn load_entry_or_default<X: MemBufferDeserialize<'a,X> + MemBufferSerialize + Default>(&self, key: usize) -> Result<X,MemBufferError> {
+        if self.is_absent(key) {
+            return Ok(X::default());
+        }
+        self.load_entry(key)
+    }
+
+    ///Loads the entry of the field with the type of the field, see `Field`
+    pub fn get<X: MemBufferDeserialize<'a,X> + MemBufferSerialize,S>(&self, field: Field<X,S>) -> Result<X,MemBufferError> {
+        self.load_entry(field.index())
+    }
+
+    ///Loads the entry of the field like `load_entry_or_default`
+    pub fn get_or_default<X: MemBufferDeserialize<'a,X> + MemBufferSerialize + Default,S>(&self, field: Field<X,S>) -> Result<X,MemBufferError> {
+        self.load_entry_or_default(field.index())
+    }
+
+    ///Checks the type of every field of the schema S, null entries and tombstones are only
+    ///accepted for nullable fields and retired slots are not checked at all. Fails with
+    ///`KeyOutOfRange` if the buffer ends before a field which is not optional and with
+    ///`FieldTypeError` if a field has another type.
+    pub fn check_schema<S: Schema>(&self) -> Result<(),MemBufferError> {
+        for field in S::fields() {
+            if field.is_retired() {
+                continue;
+            }
+            let is_type = match self.entry_type(field.index) {
+                Some(is_type) => is_type,
+                None if field.optional => continue,
+                None => return Err(MemBufferError::KeyOutOfRange { key: field.index, len: self.len() })
+            };
+            if is_type != field.type_id && !(field.nullable && self.is_absent(field.index)) {
+                return Err(MemBufferError::FieldTypeError(is_type,field.type_id));
+            }
+        }
+        Ok(())
+    }
+
+    ///Checks the layout of the buffer against the schema S once, see `check_schema`, and returns
+    ///a reader which loads the fields of the schema
+    pub fn with_schema<S: Schema>(self) -> Result<SchemaReader<'a,S,B>,MemBufferError> {
+        self.check_schema::<S>()?;
+        Ok(SchemaReader { reader: self, schema: PhantomData })
+    }
+
+    ///Returns true if the entry with the given key was stored as null, e. g. from a None
+    pub fn is_null(&self, key: usize) -> Result<bool,MemBufferError> {
+        if key >= self.len() {
+            return Err(MemBufferError::KeyOutOfRange { key, len: self.len() });
+        }
+        Ok(self.position(key).variable_type == MemBufferTypes::Null as i32)
+    }
+
+    ///Returns true if the entry with the given key was retired, see
+    ///`MemBufferWriter::add_tombstone`
+    pub fn is_retired(&self, key: usize) -> Result<bool,MemBufferError> {
+        if key >= self.len() {
+            return Err(MemBufferError::KeyOutOfRange { key, len: self.len() });
+        }
+        Ok(self.position(key).variable_type == MemBufferTypes::Tombstone as i32)
+    }
+
+    ///Loads a text entry without checking if it is valid utf-8, the key and the type are still
+    ///checked.
+    ///
+    ///# Safety
+    ///The entry must contain valid utf-8, this is the case for buffers created by the writer or
+    ///after a successful call to `verify_text_entries`.
+    pub unsafe fn load_str_unchecked(&self, key: usize) -> Result<&'a str,MemBufferError> {
+        let data: &'a [u8] = self.intern_load_entry(key, <&str>::get_mem_buffer_type())?;
+        Ok(std::str::from_utf8_unchecked(data))
+    }
+
+    ///Checks all text entries for valid utf-8 at once, this enables paying the cost of
+    ///validation once when opening the buffer and using `load_str_unchecked` afterwards.
+    ///```rust
+    ///use membuffer::{MemBufferWriter,MemBufferReader};
+    ///
+    ///let mut writer = MemBufferWriter::new();
+    ///writer.add_entry("Hello World");
+    ///let data = writer.finalize().unwrap();
+    ///
+    ///let reader = MemBufferReader::new(&data).unwrap();
+    ///reader.verify_text_entries().unwrap();
+    /////Safe as every text entry was checked before
+    ///assert_eq!(unsafe{reader.load_str_unchecked(0)}.unwrap(), "Hello World");
+    ///```
+    pub fn verify_text_entries(&self) -> Result<(),MemBufferError> {
+        let text_type = <&str>::get_mem_buffer_type();
+        for key in 0..self.len() {
+            if self.position(key).variable_type == text_type {
+                self.intern_load_entry::<&str>(key, text_type)?;
+            }
+        }
+        Ok(())
+    }
+
+    ///Loads an entry stored with the codec C and returns it, fails with `FieldTypeError` if the
+    ///entry was stored with another codec or is no serde entry at all and with `Deserialize` if
+    ///the payload cannot be decoded into T.
+    pub fn load_serde_entry_with<C: SerdeCodec, T: Deserialize<'a>>(&self,key: usize) -> Result<T,MemBufferError> {
+        let mut expected_type = C::get_mem_buffer_type();
+        //Buffers written by membuffer 0.3 stored bincode entries as plain byte vectors
+        if self.version == LEGACY_FORMAT_VERSION && expected_type == MemBufferTypes::SerdeBincode as i32 {
+            expected_type = MemBufferTypes::VectorU8.into();
+        }
+        let data: &'a [u8] = self.intern_load_entry(key, expected_type)?;
+        C::decode(data).map_err(|err| MemBufferError::Deserialize { key, source: Arc::from(err) })
+    }
+
+    ///Loads an entry stored with bincode and returns it, see `load_serde_entry_with`.
+    #[cfg(feature = "bincode")]
+    pub fn load_serde_entry<T: Deserialize<'a>>(&self,key: usize) -> Result<T,MemBufferError> {
+        self.load_serde_entry_with::<Bincode,T>(key)
+    }
+
+    ///Loads an entry stored with bincode just like `load_serde_entry` but returns the default of
+    ///T if the buffer ends before the key or the entry was stored as null or tombstone, useful for
+    ///optional fields and fields which were added later on
+    #[cfg(feature = "bincode")]
+    pub fn try_load_serde_entry_or_default<T: Deserialize<'a> + Default>(&self,key: usize) -> Result<T,MemBufferError> {
+        if self.is_absent(key) {
+            return Ok(T::default());
+        }
+        self.load_serde_entry(key)
+    }
+
+    ///Loads a nested MembufferWriter as reader
+    pub fn load_recursive_reader(&self, key: usize) -> Result<MemBufferReader<'a,B>,MemBufferError> {
+        self.intern_load_entry(key, <MemBufferWriter>::get_mem_buffer_type())
+    }
+
+    ///Loads a map written with `MemBufferWriter::add_map`
+    pub fn load_map(&self, key: usize) -> Result<MemBufferMap<'a>,MemBufferError> {
+        self.intern_load_entry(key, MemBufferTypes::HashMap.into())
+    }
+
+    ///Loads a sorted set written with `MemBufferWriter::add_sorted_set_u64` or
+    ///`MemBufferWriter::add_sorted_set_bytes`, C selects the type of the keys
+    pub fn load_sorted_set<C: SortedKeys<'a>>(&self, key: usize) -> Result<SortedSet<C>,MemBufferError> {
+        self.intern_load_entry(key, C::set_type())
+    }
+
+    ///Loads a sorted map written with `MemBufferWriter::add_sorted_map_u64` or
+    ///`MemBufferWriter::add_sorted_map_bytes`, C selects the type of the keys
+    pub fn load_sorted_map<C: SortedKeys<'a>>(&self, key: usize) -> Result<SortedMap<'a,C>,MemBufferError> {
+        self.intern_load_entry(key, C::map_type())
+    }
+
+    ///Loads an array of nested buffers written with `MemBufferWriter::add_buffer_array`
+    pub fn load_buffer_array(&self, key: usize) -> Result<BufferArray<'a,B>,MemBufferError> {
+        self.intern_load_entry(key, MemBufferTypes::BufferArray.into())
+    }
+
+
+    ///Checks every header entry against the payload, this is run by default when creating a
+    ///reader and guarantees that loading an entry never slices outside of the payload
+    fn validate_positions(&self) -> Result<(),MemBufferError> {
+        let payload_len = self.data.len();
+        for key in 0..self.len() {
+            let entry = self.position(key);
+            let (start,end) = (entry.pos.start,entry.pos.end);
+            if start < 0 || end < start || end as u64 > payload_len as u64 {
+                return Err(MemBufferError::InvalidPosition { key, start, end, payload_len });
+            }
+        }
+        let slots = self.name_slots();
+        for slot in slots.chunks_exact(NAME_FIELD_SIZE) {
+            if B::read_u64(slot) > self.len() as u64 {
+                return Err(MemBufferError::InvalidNameTable);
+            }
+        }
+        if !self.names.is_empty() && (0..self.len()).any(|key| self.name_at(key).is_none()) {
+            return Err(MemBufferError::InvalidNameTable);
+        }
+        Ok(())
+    }
+
+    ///Returns the hash table of the name table, empty if the entries have no names
+    fn name_slots(&self) -> &'a [u8] {
+        match names_table_start::<B>(self.names, self.len()) {
+            Some(start) => &self.names[NAME_FIELD_SIZE..start],
+            None => &[]
+        }
+    }
+
+    ///Returns the name of the entry with the given key, unnamed entries have an empty name
+    fn name_at(&self, key: usize) -> Option<&'a str> {
+        let start = names_table_start::<B>(self.names, self.len())?;
+        let (ends,names) = self.names[start..].split_at(self.len()*NAME_FIELD_SIZE);
+        let end = |key: usize| ends.get(key*NAME_FIELD_SIZE..(key+1)*NAME_FIELD_SIZE).map(B::read_u64);
+        let start = if key == 0 { 0 } else { end(key-1)? };
+        let end = end(key)?;
+        let name = names.get(usize::try_from(start).ok()?..usize::try_from(end).ok()?)?;
+        std::str::from_utf8(name).ok()
+    }
+
+    ///Returns the schema block of the buffer, None if the buffer was written without schema. See
+    ///`MemBufferWriter::set_schema`.
+    pub fn schema(&self) -> Result<Option<BufferSchema<'a,B>>,MemBufferError> {
+        if self.flags & FLAG_SCHEMA == 0 {
+            return Ok(None);
+        }
+        Ok(Some(BufferSchema::from_slice(self.schema)?))
+    }
+
+    ///Returns the index of the entry with the given name, the name is looked up in the hash
+    ///table of the header without scanning the other names
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        let slots = self.name_slots();
+        let capacity = slots.len()/NAME_FIELD_SIZE;
+        let mut slot = map_hash(name.as_bytes()) as usize;
+        for _ in 0..capacity {
+            slot &= capacity-1;
+            let index = B::read_u64(&slots[slot*NAME_FIELD_SIZE..]);
+            if index == 0 {
+                return None;
+            }
+            let index = usize::try_from(index-1).ok()?;
+            if self.name_at(index)? == name {
+                return Some(index);
+            }
+            slot += 1;
+        }
+        None
+    }
+
+    ///Iterates over the names and indexes of all named entries in no particular order
+    pub fn names(&self) -> impl Iterator<Item=(&'a str,usize)> + '_ {
+        self.name_slots().chunks_exact(NAME_FIELD_SIZE)
+            .filter_map(move |slot| {
+                let index = usize::try_from(B::read_u64(slot)).ok()?.checked_sub(1)?;
+                Some((self.name_at(index)?,index))
+            })
+    }
+
+    ///Loads the entry with the given name, fails with `UnknownName` if no entry has the name. See
+    ///`load_entry` and `MemBufferWriter::add_named_entry`.
+    ///```rust
+    ///use membuffer::{MemBufferWriter,MemBufferReader};
+    ///
+    ///let mut writer = MemBufferWriter::new();
+    ///writer.add_named_entry("id", 42u64).unwrap();
+    ///writer.add_named_entry("title", "Membuffer").unwrap();
+    ///let data = writer.finalize().unwrap();
+    ///
+    ///let reader = MemBufferReader::new(&data).unwrap();
+    ///assert_eq!(reader.load_named_entry::<&str>("title").unwrap(), "Membuffer");
+    ///assert_eq!(reader.index_of("id"), Some(0));
+    ///assert!(reader.load_named_entry::<u64>("name").is_err());
+    ///```
+    pub fn load_named_entry<X: MemBufferDeserialize<'a,X> + MemBufferSerialize>(&self, name: &str) -> Result<X,MemBufferError> {
+        match self.index_of(name) {
+            Some(key) => self.load_entry(key),
+            None => Err(MemBufferError::UnknownName { name: name.to_string() })
+        }
+    }
+
+    ///Creates a new memory format reader for a buffer stored in the byte order B, fails if the
+    ///header of the buffer records a different byte order. Validates every entry just like `new`.
+    ///```rust
+    ///use membuffer::{MemBufferWriter,MemBufferReader};
+    ///use byteorder::BigEndian;
+    ///
+    ///let mut writer = MemBufferWriter::<BigEndian>::with_byte_order();
+    ///writer.add_entry(100);
+    ///let data = writer.finalize().unwrap();
+    ///
+    ///assert!(MemBufferReader::new(&data).is_err());
+    ///let reader = MemBufferReader::<BigEndian>::with_byte_order(&data).unwrap();
+    ///assert_eq!(reader.load_entry::<i32>(0).unwrap(), 100);
+    ///```
+    pub fn with_byte_order(val: &'a [u8]) -> Result<MemBufferReader<'a,B>,MemBufferError> {
+        let reader = MemBufferReader::with_byte_order_unchecked(val)?;
+        reader.validate_positions()?;
+        Ok(reader)
+    }
+
+    ///Creates a new memory format reader for a buffer stored in the byte order B without
+    ///validating the positions of the entries, see `new_unchecked`.
+    pub fn with_byte_order_unchecked(val: &'a [u8]) -> Result<MemBufferReader<'a,B>,MemBufferError> {
+        let buffer = val;
+        let (version, flags, val) = if val.starts_with(MAGIC) {
+            if val.len() < PREFIX_SIZE {
+                return Err(MemBufferError::TruncatedHeader { len: val.len(), header_len: PREFIX_SIZE });
+            }
+            let version = LittleEndian::read_u16(&val[4..]);
+            if version != FORMAT_VERSION {
+                return Err(MemBufferError::UnsupportedVersion { version });
+            }
+            let flags = LittleEndian::read_u32(&val[8..]);
+            if flags & !SUPPORTED_FLAGS != 0 {
+                return Err(MemBufferError::UnsupportedFlags { flags: flags & !SUPPORTED_FLAGS });
+            }
+            (version, flags, &val[PREFIX_SIZE..])
+        } else {
+            //Buffers written by membuffer 0.3 are stored in the native byte order
+            let flags = if cfg!(target_endian = "big") { FLAG_BIG_ENDIAN } else { 0 };
+            (LEGACY_FORMAT_VERSION, flags, val)
+        };
+        let big_endian = flags & FLAG_BIG_ENDIAN != 0;
+        if big_endian != is_big_endian::<B>() {
+            return Err(MemBufferError::WrongByteOrder { big_endian });
+        }
+
+        let prefix_len = buffer.len() - val.len();
+        if val.len() < 8 {
+            return Err(MemBufferError::TruncatedHeader { len: buffer.len(), header_len: prefix_len + 8 });
+        }
+
+        let vec_len = B::read_i32(val);
+        let checksum = B::read_i32(&val[4..]);
+        if vec_len < 0 {
+            return Err(MemBufferError::WrongFormat);
+        }
+        if version == LEGACY_FORMAT_VERSION && std::num::Wrapping(checksum)+std::num::Wrapping(0x7AFECAFE) != std::num::Wrapping(vec_len) {
+            return Err(MemBufferError::WrongFormat);
+        }
+
+        let position_size = position_size(flags);
+        let vec_len = vec_len as usize;
+        let start = match vec_len.checked_mul(position_size).and_then(|x| x.checked_add(8)) {
+            Some(start) => start,
+            None => return Err(MemBufferError::WrongFormat)
+        };
+        if val.len() < start {
+            return Err(MemBufferError::TruncatedHeader { len: buffer.len(), header_len: prefix_len + start });
+        }
+
+        let (names, header_end) = if flags & FLAG_NAMES != 0 {
+            if val.len() < start + NAME_FIELD_SIZE {
+                return Err(MemBufferError::TruncatedHeader { len: buffer.len(), header_len: prefix_len + start + NAME_FIELD_SIZE });
+            }
+            let names_len = B::read_u64(&val[start..]);
+            let header_end = usize::try_from(names_len).ok()
+                .and_then(|x| x.checked_add(start + NAME_FIELD_SIZE))
+                .ok_or(MemBufferError::InvalidNameTable)?;
+            if val.len() < header_end {
+                return Err(MemBufferError::TruncatedHeader { len: buffer.len(), header_len: prefix_len + header_end });
+            }
+            let names = &val[start+NAME_FIELD_SIZE..header_end];
+            if names_table_start::<B>(names, vec_len).is_none() {
+                return Err(MemBufferError::InvalidNameTable);
+            }
+            (names, header_end)
+        } else {
+            (&val[start..start], start)
+        };
+
+        let (schema, header_end) = if flags & FLAG_SCHEMA != 0 {
+            let schema_start = header_end + 8;
+            if val.len() < schema_start {
+                return Err(MemBufferError::TruncatedHeader { len: buffer.len(), header_len: prefix_len + schema_start });
+            }
+            let schema_len = B::read_u64(&val[header_end..]);
+            match usize::try_from(schema_len).ok().and_then(|x| x.checked_add(schema_start)) {
+                Some(schema_end) if schema_end <= val.len() => (&val[schema_start..schema_end], schema_end),
+                _ => {
+                    let header_len = usize::try_from(schema_len).unwrap_or(usize::MAX).saturating_add(prefix_len + schema_start);
+                    return Err(MemBufferError::TruncatedHeader { len: buffer.len(), header_len });
+                }
+            }
+        } else {
+            (&val[header_end..header_end], header_end)
+        };
+
+        if version != LEGACY_FORMAT_VERSION {
+            //The checksum covers the header before and after itself
+            let crc = crc32c::crc32c(&buffer[..prefix_len+4]);
+            let crc = crc32c::crc32c_append(crc, &val[8..header_end]);
+            if crc != checksum as u32 {
+                return Err(MemBufferError::HeaderChecksumMismatch);
+            }
+        }
+
+        Ok(MemBufferReader {
+            version,
+            flags,
+            position_size,
+            lazy_checksums: true,
+            offsets: &val[8..start],
+            names,
+            schema,
+            data: &val[header_end..],
+            byte_order: PhantomData
+        })
+    }
+}
+
+impl<B: ByteOrder> std::fmt::Debug for MemBufferReader<'_,B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f,"Found memory buffer with payload size {}",self.data.len())
+    }
+}
+
+
+///The Writer class which sets up the schema and writes it into the memory when finished building.
+///All numbers are written in the byte order B which is little endian by default.
+pub struct MemBufferWriter<B: ByteOrder = LittleEndian> {
+    types: Vec<i32>,
+    alignments: Vec<usize>,
+    data: Vec<Vec<u8>>,
+    names: std::collections::HashMap<String,usize>,
+    schema: bool,
+    registry: std::collections::BTreeMap<i32,String>,
+    checksums: bool,
+    offset_width: OffsetWidth,
+    //Largest offset written with 32 bit offsets, the tests lower it to make overflowing cheap
+    #[cfg(test)]
+    max_offset_32: usize,
+    error: Option<MemBufferError>,
+    byte_order: PhantomData<B>
+}
+
+pub trait MemBufferSerialize {
+    ///Returns the payload of the value, numbers have to be written in the byte order B
+    fn to_mem_buffer<'a, B: ByteOrder>(&'a self) -> std::borrow::Cow<'a,[u8]>;
+    fn get_mem_buffer_type() -> i32; 
+
+    ///The alignment the payload of this type requires relative to the start of the buffer, the
+    ///writer pads the payload accordingly to enable zero copy loading of slices
+    fn get_mem_buffer_alignment() -> usize {
+        1
+    }
+
+    ///Returns true if the value has to be stored as null entry instead of with its type, only
+    ///nullable types like Option have such values
+    fn is_mem_buffer_null(&self) -> bool {
+        false
+    }
+
+    ///Returns true if values of this type may be stored as null entry
+    fn is_mem_buffer_nullable() -> bool {
+        false
+    }
+
+    ///Returns an error if the payload cannot be stored in a buffer written in the byte order B,
+    ///nested buffers keep the byte order they were written in and cannot be converted
+    fn check_mem_buffer_byte_order<B: ByteOrder>(&self) -> Result<(),MemBufferError> {
+        Ok(())
+    }
+}
+
+///Returns the type id the value is stored with
+fn entry_type<T: MemBufferSerialize>(val: &T) -> i32 {
+    if val.is_mem_buffer_null() {
+        return MemBufferTypes::Null.into();
+    }
+    T::get_mem_buffer_type()
+}
+
+impl<T: MemBufferSerialize> MemBufferSerialize for Option<T> {
+    fn to_mem_buffer<'a, B: ByteOrder>(&'a self) -> Cow<'a,[u8]> {
+        match self {
+            Some(val) => val.to_mem_buffer::<B>(),
+            None => Cow::Borrowed(&[])
+        }
+    }
+
+    fn get_mem_buffer_type() -> i32 {
+        T::get_mem_buffer_type()
+    }
+
+    fn get_mem_buffer_alignment() -> usize {
+        T::get_mem_buffer_alignment()
+    }
+
+    fn is_mem_buffer_null(&self) -> bool {
+        self.is_none()
+    }
+
+    fn is_mem_buffer_nullable() -> bool {
+        true
+    }
+
+    fn check_mem_buffer_byte_order<B: ByteOrder>(&self) -> Result<(),MemBufferError> {
+        match self {
+            Some(val) => val.check_mem_buffer_byte_order::<B>(),
+            None => Ok(())
+        }
+    }
+}
+
+impl MemBufferSerialize for &str {
+    fn to_mem_buffer<'a, B: ByteOrder>(&'a self) -> std::borrow::Cow<'a,[u8]> {
+        std::borrow::Cow::Borrowed(self.as_bytes())
+    }
+
+    fn get_mem_buffer_type() -> i32 {
+        MemBufferTypes::Text.into()
+    }
+}
+
+impl MemBufferSerialize for &String {
+    fn to_mem_buffer<'a, B: ByteOrder>(&'a self) -> Cow<'a,[u8]> {
+        Cow::Borrowed(self.as_bytes())
+    }
+
+    fn get_mem_buffer_type() -> i32 {
+        MemBufferTypes::Text.into()
+    }
+}
+
+impl MemBufferSerialize for String {
+    fn to_mem_buffer<'a, B: ByteOrder>(&'a self) -> Cow<'a,[u8]> {
+        Cow::Borrowed(self.as_bytes())
+    }
+
+    fn get_mem_buffer_type() -> i32 {
+        MemBufferTypes::Text.into()
+    }
+}
+
+impl MemBufferSerialize for &[u8] {
+    fn to_mem_buffer<'a, B: ByteOrder>(&'a self) -> Cow<'a, [u8]> {
+        Cow::Borrowed(self)
+    }
+
+    fn get_mem_buffer_type() -> i32 {
+        MemBufferTypes::VectorU8.into()
+    }
+}
+
+impl_mem_buffer_owned_slice!(Cow<'_,[u8]>, u8);
+impl_mem_buffer_owned_slice!(Vec<u8>, u8);
+impl_mem_buffer_owned_slice!(Box<[u8]>, u8);
+
+impl<O: ByteOrder> MemBufferSerialize for MemBufferWriter<O> {
+    //The nested buffer keeps its own byte order which is recorded in its header. Overflowing 32
+    //bit offsets cannot be reported from here, therefore the nested buffer switches to 64 bit
+    //offsets when needed even if 32 bit offsets were requested
+    fn to_mem_buffer<'a, B: ByteOrder>(&'a self) -> Cow<'a,[u8]> {
+        let schema = self.schema_block();
+        let wide = self.offset_width == OffsetWidth::Bits64 || self.overflowing_key(schema.len()).is_some();
+        Cow::Owned(self.intern_finalize(wide, &schema))
+    }
+
+    fn get_mem_buffer_type() -> i32 {
+        MemBufferTypes::MemBuffer.into()
+    }
+
+    fn get_mem_buffer_alignment() -> usize {
+        MAX_ALIGNMENT
+    }
+
+    //A nested buffer written in another byte order could not be read through the parent reader,
+    //neither can a nested buffer which failed to add one of its own entries
+    fn check_mem_buffer_byte_order<B: ByteOrder>(&self) -> Result<(),MemBufferError> {
+        if is_big_endian::<O>() != is_big_endian::<B>() {
+            return Err(MemBufferError::WrongByteOrder { big_endian: is_big_endian::<O>() });
+        }
+        match &self.error {
+            Some(err) => Err(err.clone()),
+            None => Ok(())
+        }
+    }
+}
+
+impl<B: ByteOrder> Default for MemBufferWriter<B> {
+    fn default() -> Self {
+        MemBufferWriter::with_byte_order()
+    }
+}
+
+impl MemBufferWriter {
+    ///Creates a new empty memory format writer
+    pub fn new() -> MemBufferWriter {
+        MemBufferWriter::with_byte_order()
+    }
+
+    ///Create a new Membuffer writer from the given memory, this will enable the writer to add
+    ///more data to the previous version, to do so the writer does a full reload of the memory
+    ///therefore it is an expensive operation if the structure adding fields to is huge.
+    ///```rust
+    ///use membuffer::{MemBufferWriter,MemBufferReader};
+    ///
+    ///let mut value = MemBufferWriter::new();
+    ///value.add_entry("Hello");
+    ///value.add_entry("World");
+    ///
+    ///let data = value.finalize().unwrap();
+    ///
+    /////Save data to disk or anything like that
+    /////Then load it again and add more data by doing this
+    ///
+    ///let mut writer_adder = MemBufferWriter::from(&data).unwrap();
+    /////The writer creates a new Vector to hold the data therefore no mutable reference to data is
+    /////stored
+    ///writer_adder.add_entry("Damn I forgot");
+    ///
+    ///let new_data = writer_adder.finalize().unwrap();
+    /////new_data will now contain an entry for "Hello" an entry for "World" and an entry
+    /////for "Damn I forgot" 
+    ///
+    ///```
+    pub fn from(raw_memory: &[u8]) -> Result<MemBufferWriter,MemBufferError> {
+        MemBufferWriter::from_with_byte_order(raw_memory)
+    }
+
+    ///Serializes the integer to the memory slice in the byte order of this machine like the
+    ///headers of the 0.3 format, buffers of the current format are little endian by default
+    pub fn serialize_i32_to(val: i32, to: &mut Vec<u8>) {
+        to.write_i32::<NativeEndian>(val).unwrap();
+    }
+}
+
+impl<B: ByteOrder> MemBufferWriter<B> {
+    ///Creates a new empty memory format writer which writes all numbers in the byte order B
+    pub fn with_byte_order() -> MemBufferWriter<B> {
+        MemBufferWriter {
+            types: Vec::new(),
+            alignments: Vec::new(),
+            data: Vec::new(),
+            names: std::collections::HashMap::new(),
+            schema: false,
+            registry: std::collections::BTreeMap::new(),
+            checksums: true,
+            offset_width: OffsetWidth::Auto,
+            #[cfg(test)]
+            max_offset_32: MAX_OFFSET_32,
+            error: None,
+            byte_order: PhantomData
+        }
+    }
+
+    ///Create a new Membuffer writer from memory stored in the byte order B, see `from`
+    pub fn from_with_byte_order(raw_memory: &[u8]) -> Result<MemBufferWriter<B>,MemBufferError> {
+        let reader = MemBufferReader::<B>::with_byte_order(raw_memory)?;
+        let header_len = raw_memory.len()-reader.payload_len();
+        let mut types : Vec<i32> = Vec::new();
+        let mut alignments : Vec<usize> = Vec::new();
+        let mut data : Vec<Vec<u8>> = Vec::new();
+        for key in 0..reader.len() {
+            let x = reader.position(key);
+            types.push(x.variable_type);
+            //The type of the entry is unknown, keep whatever alignment the entry had before
+            let absolute = header_len + x.pos.start as usize;
+            alignments.push((1 << absolute.trailing_zeros()).min(MAX_ALIGNMENT));
+            data.push(reader.data[x.pos.start as usize..x.pos.end as usize].to_vec())
+        }
+
+        let names = reader.names().map(|(name,key)| (name.to_string(),key)).collect();
+        let mut registry = std::collections::BTreeMap::new();
+        if let Some(schema) = reader.schema()? {
+            for val in schema.registry() {
+                let (id,name) = val?;
+                registry.insert(id, name.to_string());
+            }
+        }
+
+        Ok(MemBufferWriter {
+            types,
+            alignments,
+            data,
+            names,
+            schema: reader.flags() & FLAG_SCHEMA != 0,
+            registry,
+            checksums: true,
+            offset_width: OffsetWidth::Auto,
+            #[cfg(test)]
+            max_offset_32: MAX_OFFSET_32,
+            error: None,
+            byte_order: PhantomData
+        })
+    }
+
+    ///Adds an entry to the writer the only requirement is the serializable trait. Nested writers
+    ///have to use the byte order of this writer, otherwise `finalize` fails with
+    ///`WrongByteOrder`.
+    pub fn add_entry<T: MemBufferSerialize>(&mut self, val: T) {
+        self.record_error(&val);
+        let slice = val.to_mem_buffer::<B>();
+        self.types.push(entry_type(&val));
+        self.alignments.push(T::get_mem_buffer_alignment());
+        self.data.push(slice.to_vec());
+    }
+
+    ///Adds an entry which can be loaded by its name with `MemBufferReader::load_named_entry` as
+    ///well as by its index. Fails with `DuplicateName` if another entry has the same name,
+    ///nothing is added in this case.
+    pub fn add_named_entry<T: MemBufferSerialize>(&mut self, name: &str, val: T) -> Result<(),MemBufferError> {
+        if self.names.contains_key(name) {
+            return Err(MemBufferError::DuplicateName { name: name.to_string() });
+        }
+        val.check_mem_buffer_byte_order::<B>()?;
+        self.add_entry(val);
+        self.names.insert(name.to_string(), self.types.len()-1);
+        Ok(())
+    }
+
+    ///Names the entry at the given index, this can be used for entries added by any of the add
+    ///functions. A previous name of the entry is replaced. Fails with `DuplicateName` if another
+    ///entry has the same name.
+    pub fn name_entry(&mut self, index: usize, name: &str) -> Result<(),MemBufferError> {
+        self.check_index(index)?;
+        match self.names.get(name) {
+            Some(&other) if other != index => return Err(MemBufferError::DuplicateName { name: name.to_string() }),
+            Some(_) => return Ok(()),
+            None => {}
+        }
+        self.names.retain(|_,other| *other != index);
+        self.names.insert(name.to_string(), index);
+        Ok(())
+    }
+
+    ///Stores the value as entry of the field, replacing the entry if it was already stored. If
+    ///the index of the field is past the last entry the entries in between are added as null
+    ///entries which can be filled later on.
+    pub fn put<T: MemBufferSerialize,S>(&mut self, field: Field<T,S>, val: T) {
+        while self.types.len() < field.index() {
+            self.add_entry(None::<&str>);
+        }
+        if field.index() == self.types.len() {
+            self.add_entry(val);
+        } else {
+            self.record_error(&val);
+            self.data[field.index()] = val.to_mem_buffer::<B>().to_vec();
+            self.types[field.index()] = entry_type(&val);
+            self.alignments[field.index()] = T::get_mem_buffer_alignment();
+        }
+    }
+
+    ///Remembers the first value which cannot be stored in this writer, the error is returned by
+    ///`finalize` as adding entries does not fail
+    fn record_error<T: MemBufferSerialize>(&mut self, val: &T) {
+        if self.error.is_none() {
+            self.error = val.check_mem_buffer_byte_order::<B>().err();
+        }
+    }
+
+    ///Returns the index of the entry with the given name
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.names.get(name).copied()
+    }
+
+    ///Returns an error if there is no entry with the given index
+    fn check_index(&self, index: usize) -> Result<(),MemBufferError> {
+        if index >= self.types.len() {
+            return Err(MemBufferError::KeyOutOfRange { key: index, len: self.types.len() });
+        }
+        Ok(())
+    }
+
+    ///Replaces the entry at the given index, fails if the index was never added
+    pub fn set_entry<T: MemBufferSerialize>(&mut self, val: T, index: usize) -> Result<(),MemBufferError> {
+        self.check_index(index)?;
+        val.check_mem_buffer_byte_order::<B>()?;
+        self.data[index] = val.to_mem_buffer::<B>().to_vec();
+        self.types[index] = entry_type(&val);
+        self.alignments[index] = T::get_mem_buffer_alignment();
+        Ok(())
+    }
+
+    ///Adds a tombstone which marks the slot of a removed field, readers load it as None if the
+    ///field was nullable and fail with `RetiredEntry` otherwise. See `check_compatibility`.
+    pub fn add_tombstone(&mut self) {
+        self.types.push(MemBufferTypes::Tombstone.into());
+        self.alignments.push(1);
+        self.data.push(Vec::new());
+    }
+
+    ///Replaces the entry at the given index with a tombstone, see `add_tombstone`
+    pub fn retire_entry(&mut self, index: usize) -> Result<(),MemBufferError> {
+        self.check_index(index)?;
+        self.types[index] = MemBufferTypes::Tombstone.into();
+        self.alignments[index] = 1;
+        self.data[index] = Vec::new();
+        Ok(())
+    }
+
+    ///Marks the byte vector entry at the given index as bincode entry. Buffers written by
+    ///membuffer 0.3 stored bincode entries as plain byte vectors which cannot be told apart from
+    ///other byte vectors, after migrating such a buffer with `from` its bincode entries have to be
+    ///marked to keep them loadable with `load_serde_entry`. Fails with `FieldTypeError` if the
+    ///entry is not a byte vector.
+    #[cfg(feature = "bincode")]
+    pub fn mark_serde_entry(&mut self, index: usize) -> Result<(),MemBufferError> {
+        self.check_index(index)?;
+        if self.types[index] != MemBufferTypes::VectorU8 as i32 {
+            return Err(MemBufferError::FieldTypeError(self.types[index],MemBufferTypes::VectorU8.into()));
+        }
+        self.types[index] = MemBufferTypes::SerdeBincode.into();
+        Ok(())
+    }
+
+    pub fn load_entry<'a, T: MemBufferDeserialize<'a,T>+MemBufferSerialize>(&'a self, index: usize) -> Result<T,MemBufferError> {
+        self.check_index(index)?;
+        if self.types[index] == MemBufferTypes::Tombstone as i32 && T::get_mem_buffer_type() != self.types[index] {
+            return T::from_mem_buffer_null(T::get_mem_buffer_type()).map_err(|_| MemBufferError::RetiredEntry { key: index });
+        }
+        if self.types[index] == MemBufferTypes::Null as i32 && T::get_mem_buffer_type() != self.types[index] {
+            return T::from_mem_buffer_null(T::get_mem_buffer_type());
+        }
+        if T::get_mem_buffer_type() != self.types[index] {
+            return Err(MemBufferError::FieldTypeError(self.types[index],T::get_mem_buffer_type()));
+        }
+        T::from_mem_buffer::<B>(&self.data[index])
+    }
+
+    pub fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
+    ///Enables or disables storing a CRC32C of every payload in the header, this is enabled by
+    ///default. The header itself is always protected by a checksum.
+    pub fn set_checksums(&mut self, enabled: bool) {
+        self.checksums = enabled;
+    }
+
+    ///Enables writing a schema block into the header which describes every entry, see
+    ///`BufferSchema`. This is disabled by default.
+    pub fn set_schema(&mut self, enabled: bool) {
+        self.schema = enabled;
+    }
+
+    ///Registers the name of an own type id for the schema block, see `set_schema`
+    pub fn register_type(&mut self, type_id: i32, name: &str) {
+        self.registry.insert(type_id, name.to_string());
+    }
+
+    ///Registers the type id of the plain old data type T under its `POD_NAME`
+    pub fn register_pod<T: MemBufferPod>(&mut self) {
+        self.register_type(pod_type_id::<T>(), T::POD_NAME);
+    }
+
+    ///Selects the width of the entry offsets in the header, by default 32 bit offsets are used
+    ///unless the payload exceeds 2 GiB.
+    pub fn set_offset_width(&mut self, width: OffsetWidth) {
+        self.offset_width = width;
+    }
+
+    ///Adds a serde serializable entry into the structure, the value is encoded with the codec C
+    ///and stored under the type id of the codec. Fails with `Serialize` if the value cannot be
+    ///encoded, nothing is added in this case.
+    pub fn add_serde_entry_with<C: SerdeCodec, T: Serialize>(&mut self,val: &T) -> Result<(),MemBufferError> {
+        let encoded = C::encode(val).map_err(|err| MemBufferError::Serialize(Arc::from(err)))?;
+        self.types.push(C::get_mem_buffer_type());
+        self.alignments.push(1);
+        self.data.push(encoded);
+        Ok(())
+    }
+
+    ///Adds all buffers as one entry which stores an offset table of the buffers, see
+    ///`MemBufferReader::load_buffer_array`. Every buffer is aligned like a nested buffer and
+    ///uses the byte order of this writer.
+    ///```rust
+    ///use membuffer::{MemBufferWriter,MemBufferReader};
+    ///
+    ///let children = (0..3).map(|x| {
+    ///    let mut child = MemBufferWriter::new();
+    ///    child.add_entry(x);
+    ///    child
+    ///});
+    ///let mut writer = MemBufferWriter::new();
+    ///writer.add_buffer_array(children);
+    ///let data = writer.finalize().unwrap();
+    ///
+    ///let reader = MemBufferReader::new(&data).unwrap();
+    ///let array = reader.load_buffer_array(0).unwrap();
+    ///assert_eq!(array.len(), 3);
+    ///assert_eq!(array.get(2).unwrap().load_entry::<i32>(0).unwrap(), 2);
+    ///```
+    pub fn add_buffer_array<W: Borrow<MemBufferWriter<B>>, I: IntoIterator<Item=W>>(&mut self, buffers: I) {
+        let buffers: Vec<Vec<u8>> = buffers.into_iter()
+            .map(|x| { self.record_error(x.borrow()); x.borrow().to_mem_buffer::<B>().into_owned() })
+            .collect();
+        let table_len = LIST_OFFSET_SIZE*(1+2*buffers.len());
+        let mut data = Vec::with_capacity(table_len + buffers.iter().map(|x| x.len()+MAX_ALIGNMENT).sum::<usize>());
+        data.write_u64::<B>(buffers.len() as u64).unwrap();
+        let mut offsets = Vec::with_capacity(buffers.len());
+        let mut offset = 0;
+        for x in &buffers {
+            offset += (MAX_ALIGNMENT - (table_len + offset) % MAX_ALIGNMENT) % MAX_ALIGNMENT;
+            data.write_u64::<B>(offset as u64).unwrap();
+            data.write_u64::<B>((offset + x.len()) as u64).unwrap();
+            offsets.push(offset);
+            offset += x.len();
+        }
+        for (x,offset) in buffers.iter().zip(offsets) {
+            data.resize(table_len + offset, 0);
+            data.extend_from_slice(x);
+        }
+        self.types.push(MemBufferTypes::BufferArray.into());
+        self.alignments.push(MAX_ALIGNMENT);
+        self.data.push(data);
+    }
+
+    ///Adds all pairs as one map entry holding a hash table which is built here, see
+    ///`MemBufferReader::load_map`. All values have the same type, fails with `DuplicateKey` if
+    ///a key is given twice and with `NullableMapValue` if the values have a nullable type like
+    ///Option.
+    ///```rust
+    ///use membuffer::{MemBufferWriter,MemBufferReader};
+    ///
+    ///let mut writer = MemBufferWriter::new();
+    ///writer.add_map(vec![("one",1),("two",2)]).unwrap();
+    ///let data = writer.finalize().unwrap();
+    ///
+    ///let reader = MemBufferReader::new(&data).unwrap();
+    ///let map = reader.load_map(0).unwrap();
+    ///assert_eq!(map.get_as::<i32,_>("two").unwrap(), Some(2));
+    ///assert!(!map.contains_key("three").unwrap());
+    ///```
+    pub fn add_map<K: AsRef<[u8]>, V: MemBufferSerialize, I: IntoIterator<Item=(K,V)>>(&mut self, pairs: I) -> Result<(),MemBufferError> {
+        if V::is_mem_buffer_nullable() {
+            return Err(MemBufferError::NullableMapValue);
+        }
+        let pairs: Vec<(K,Vec<u8>)> = pairs.into_iter()
+            .map(|(key,val)| { val.check_mem_buffer_byte_order::<B>()?; let val = val.to_mem_buffer::<B>().into_owned(); Ok((key,val)) })
+            .collect::<Result<_,MemBufferError>>()?;
+        let capacity = (2*pairs.len()).next_power_of_two();
+        let mut slots = vec![(0u64,0u64); capacity];
+        for (index,(key,_)) in pairs.iter().enumerate() {
+            let hash = map_hash(key.as_ref());
+            let mut slot = hash as usize & (capacity-1);
+            while slots[slot].1 != 0 {
+                if slots[slot].0 == hash && pairs[slots[slot].1 as usize-1].0.as_ref() == key.as_ref() {
+                    return Err(MemBufferError::DuplicateKey { index });
+                }
+                slot = (slot+1) & (capacity-1);
+            }
+            slots[slot] = (hash,index as u64+1);
+        }
+
+        let table_len = MAP_HEADER_SIZE + capacity*MAP_SLOT_SIZE + pairs.len()*MAP_RECORD_SIZE;
+        let alignment = V::get_mem_buffer_alignment();
+        let mut data = Vec::with_capacity(table_len);
+        data.write_u64::<B>(pairs.len() as u64).unwrap();
+        data.write_u64::<B>(capacity as u64).unwrap();
+        data.write_i32::<B>(V::get_mem_buffer_type()).unwrap();
+        data.write_u32::<B>(0).unwrap();
+        for (hash,index) in slots {
+            data.write_u64::<B>(hash).unwrap();
+            data.write_u64::<B>(index).unwrap();
+        }
+        let mut offsets = Vec::with_capacity(pairs.len());
+        let mut offset = 0;
+        for (key,val) in &pairs {
+            let key_start = offset;
+            offset += key.as_ref().len();
+            offset += (alignment - (table_len + offset) % alignment) % alignment;
+            for x in [key_start, key_start + key.as_ref().len(), offset, offset + val.len()].iter() {
+                data.write_u64::<B>(*x as u64).unwrap();
+            }
+            offsets.push(offset);
+            offset += val.len();
+        }
+        for ((key,val),offset) in pairs.iter().zip(offsets) {
+            data.extend_from_slice(key.as_ref());
+            data.resize(table_len + offset, 0);
+            data.extend_from_slice(val);
+        }
+        self.types.push(MemBufferTypes::HashMap.into());
+        self.alignments.push(MAX_ALIGNMENT);
+        self.data.push(data);
+        Ok(())
+    }
+
+    ///Adds the numbers as sorted set entry, they are sorted and duplicates are removed. See
+    ///`MemBufferReader::load_sorted_set`.
+    pub fn add_sorted_set_u64<I: IntoIterator<Item=u64>>(&mut self, keys: I) {
+        let mut keys: Vec<u64> = keys.into_iter().collect();
+        keys.sort_unstable();
+        keys.dedup();
+        self.types.push(MemBufferTypes::SortedSetU64.into());
+        self.alignments.push(std::mem::align_of::<u64>());
+        self.data.push(u64_keys_to_bytes::<B>(&keys));
+    }
+
+    ///Adds the byte strings as sorted set entry, they are sorted and duplicates are removed. See
+    ///`MemBufferReader::load_sorted_set`.
+    pub fn add_sorted_set_bytes<K: AsRef<[u8]>, I: IntoIterator<Item=K>>(&mut self, keys: I) {
+        let mut keys: Vec<K> = keys.into_iter().collect();
+        keys.sort_unstable_by(|x,y| x.as_ref().cmp(y.as_ref()));
+        keys.dedup_by(|x,y| x.as_ref() == y.as_ref());
+        self.types.push(MemBufferTypes::SortedSetBytes.into());
+        self.alignments.push(std::mem::align_of::<u64>());
+        self.data.push(list_to_bytes::<B,_>(keys.iter().map(|x| x.as_ref())).into_owned());
+    }
+
+    ///Adds the pairs as sorted map entry with the numbers as keys, the pairs are sorted by their
+    ///keys. Fails with `DuplicateKey` if a key is given twice and with `NullableMapValue` if the
+    ///values have a nullable type. See `MemBufferReader::load_sorted_map`.
+    pub fn add_sorted_map_u64<V: MemBufferSerialize, I: IntoIterator<Item=(u64,V)>>(&mut self, pairs: I) -> Result<(),MemBufferError> {
+        self.add_sorted_map::<u64,V,_>(pairs, MemBufferTypes::SortedMapU64, |keys| u64_keys_to_bytes::<B>(keys))
+    }
+
+    ///Adds the pairs as sorted map entry with the byte strings as keys, the pairs are sorted by
+    ///their keys. Fails with `DuplicateKey` if a key is given twice and with `NullableMapValue`
+    ///if the values have a nullable type. See `MemBufferReader::load_sorted_map`.
+    pub fn add_sorted_map_bytes<K: AsRef<[u8]> + Ord, V: MemBufferSerialize, I: IntoIterator<Item=(K,V)>>(&mut self, pairs: I) -> Result<(),MemBufferError> {
+        self.add_sorted_map::<K,V,_>(pairs, MemBufferTypes::SortedMapBytes, |keys| list_to_bytes::<B,_>(keys.iter().map(|x| x.as_ref())).into_owned())
+    }
+
+    fn add_sorted_map<K: Ord, V: MemBufferSerialize, I: IntoIterator<Item=(K,V)>>(&mut self, pairs: I, map_type: MemBufferTypes, keys_to_bytes: impl Fn(&[K]) -> Vec<u8>) -> Result<(),MemBufferError> {
+        if V::is_mem_buffer_nullable() {
+            return Err(MemBufferError::NullableMapValue);
+        }
+        let mut pairs: Vec<(K,usize,Vec<u8>)> = pairs.into_iter()
+            .enumerate()
+            .map(|(index,(key,val))| { val.check_mem_buffer_byte_order::<B>()?; let val = val.to_mem_buffer::<B>().into_owned(); Ok((key,index,val)) })
+            .collect::<Result<_,MemBufferError>>()?;
+        pairs.sort_by(|x,y| x.0.cmp(&y.0));
+        if let Some(x) = pairs.windows(2).find(|x| x[0].0 == x[1].0) {
+            return Err(MemBufferError::DuplicateKey { index: x[0].1.max(x[1].1) });
+        }
+        let (keys,values): (Vec<K>,Vec<Vec<u8>>) = pairs.into_iter().map(|(key,_,val)| (key,val)).unzip();
+        let keys = keys_to_bytes(&keys);
+
+        let ranges_start = (SORTED_MAP_HEADER_SIZE + keys.len()).div_ceil(LIST_OFFSET_SIZE)*LIST_OFFSET_SIZE;
+        let table_len = ranges_start + values.len()*2*LIST_OFFSET_SIZE;
+        let alignment = V::get_mem_buffer_alignment();
+        let mut data = Vec::with_capacity(table_len + values.iter().map(|x| x.len()+alignment).sum::<usize>());
+        data.write_i32::<B>(V::get_mem_buffer_type()).unwrap();
+        data.write_u32::<B>(0).unwrap();
+        data.write_u64::<B>(keys.len() as u64).unwrap();
+        data.extend_from_slice(&keys);
+        data.resize(ranges_start, 0);
+        let mut offsets = Vec::with_capacity(values.len());
+        let mut offset = 0;
+        for val in &values {
+            offset += (alignment - (table_len + offset) % alignment) % alignment;
+            data.write_u64::<B>(offset as u64).unwrap();
+            data.write_u64::<B>((offset + val.len()) as u64).unwrap();
+            offsets.push(offset);
+            offset += val.len();
+        }
+        for (val,offset) in values.iter().zip(offsets) {
+            data.resize(table_len + offset, 0);
+            data.extend_from_slice(val);
+        }
+        self.types.push(map_type.into());
+        self.alignments.push(MAX_ALIGNMENT);
+        self.data.push(data);
+        Ok(())
+    }
+
+    ///Adds a serde serializable entry into the structure, the value is encoded with bincode.
+    #[cfg(feature = "bincode")]
+    pub fn add_serde_entry<T: Serialize>(&mut self,val: &T) -> Result<(),MemBufferError> {
+        self.add_serde_entry_with::<Bincode,T>(val)
+    }
+
+
+    ///Finalize the schema and return the memory slice holding the whole vector. Every payload
+    ///is padded to the alignment of its type relative to the start of the returned vector. Fails
+    ///with `OffsetOverflow` if the payload does not fit into 32 bit offsets and the offset width
+    ///was set to `OffsetWidth::Bits32` and with `WrongByteOrder` if a nested writer with another
+    ///byte order was added.
+    pub fn finalize(&self) -> Result<Vec<u8>,MemBufferError> {
+        if let Some(err) = &self.error {
+            return Err(err.clone());
+        }
+        let schema = self.schema_block();
+        let wide = match self.offset_width {
+            OffsetWidth::Auto => self.overflowing_key(schema.len()).is_some(),
+            OffsetWidth::Bits32 => match self.overflowing_key(schema.len()) {
+                Some(key) => return Err(MemBufferError::OffsetOverflow { key }),
+                None => false
+            },
+            OffsetWidth::Bits64 => true,
+        };
+        Ok(self.intern_finalize(wide, &schema))
+    }
+
+    ///Returns the name of a registered or built-in type
+    fn type_name(&self, type_id: i32) -> Option<&str> {
+        self.registry.get(&type_id).map(|x| x.as_str()).or_else(|| builtin_type_name(type_id))
+    }
+
+    ///Returns the name of the type of the elements of the entry, the value type of maps is read
+    ///from their payload
+    fn element_type_name(&self, index: usize) -> Option<&str> {
+        let (type_id,data) = (self.types[index],&self.data[index]);
+        let value_type = if type_id == MemBufferTypes::HashMap as i32 {
+            data.get(16..20).map(B::read_i32)
+        } else if type_id == MemBufferTypes::SortedMapU64 as i32 || type_id == MemBufferTypes::SortedMapBytes as i32 {
+            data.get(..4).map(B::read_i32)
+        } else {
+            return BUILTIN_TYPE_NAMES.iter().find(|x| x.0 == type_id).map(|x| x.2).filter(|x| !x.is_empty());
+        };
+        self.type_name(value_type?)
+    }
+
+    ///Builds the schema block of the buffer, empty if the schema is disabled. See `BufferSchema`
+    ///for its layout.
+    fn schema_block(&self) -> Vec<u8> {
+        if !self.schema {
+            return Vec::new();
+        }
+        let mut names = vec![""; self.types.len()];
+        for (name,&index) in &self.names {
+            names[index] = name;
+        }
+        let type_names: Vec<&str> = self.types.iter().map(|x| self.type_name(*x).unwrap_or("")).collect();
+        let element_types: Vec<&str> = (0..self.types.len()).map(|x| self.element_type_name(x).unwrap_or("")).collect();
+        let nested: Vec<&[u8]> = self.types.iter().zip(&self.data)
+            .map(|(type_id,data)| match MemBufferReader::<B>::with_byte_order_unchecked(data) {
+                Ok(reader) if *type_id == MemBufferTypes::MemBuffer as i32 => reader.schema,
+                _ => &[]
+            })
+            .collect();
+        let registry_ids: Vec<i32> = self.registry.keys().copied().collect();
+        let registry_names: Vec<&str> = self.registry.values().map(|x| x.as_str()).collect();
+
+        //The schema block is covered by the checksum of the header
+        let mut schema = MemBufferWriter::<B>::with_byte_order();
+        schema.set_checksums(false);
+        schema.add_entry(&names[..]);
+        schema.add_entry(&self.types[..]);
+        schema.add_entry(&type_names[..]);
+        schema.add_entry(&element_types[..]);
+        schema.add_entry(&nested[..]);
+        schema.add_entry(&registry_ids[..]);
+        schema.add_entry(&registry_names[..]);
+        let wide = schema.overflowing_key(0).is_some();
+        schema.intern_finalize(wide, &[])
+    }
+
+    ///The flags written into the header of the buffer
+    fn header_flags(&self, wide: bool) -> u32 {
+        let mut flags = FLAG_ALIGNED;
+        if is_big_endian::<B>() {
+            flags |= FLAG_BIG_ENDIAN;
+        }
+        if self.checksums {
+            flags |= FLAG_CHECKSUMS;
+        }
+        if wide {
+            flags |= FLAG_OFFSETS_64;
+        }
+        if !self.names.is_empty() {
+            flags |= FLAG_NAMES;
+        }
+        if self.schema {
+            flags |= FLAG_SCHEMA;
+        }
+        flags
+    }
+
+    ///Capacity of the hash table in the name table, twice the number of names to keep the probe
+    ///sequences short
+    fn names_capacity(&self) -> usize {
+        (2*self.names.len()).next_power_of_two()
+    }
+
+    ///The length of the header with positions of the given size including the name table and
+    ///the schema block of the given length
+    fn header_len(&self, position_size: usize, schema_len: usize) -> usize {
+        let mut len = PREFIX_SIZE + 8 + self.types.len()*position_size;
+        if !self.names.is_empty() {
+            len += NAME_FIELD_SIZE*(2 + self.names_capacity() + self.types.len());
+            len += self.names.keys().map(|x| x.len()).sum::<usize>();
+        }
+        if self.schema {
+            len += 8 + schema_len;
+        }
+        len
+    }
+
+    ///Writes the name table with its length, the hash table is filled in the order of the entries
+    ///to keep the output deterministic
+    fn write_names(&self, var: &mut Vec<u8>) {
+        let mut names: Vec<Option<&str>> = vec![None; self.types.len()];
+        for (name,&index) in &self.names {
+            names[index] = Some(name);
+        }
+        let capacity = self.names_capacity();
+        let mut slots = vec![0u64; capacity];
+        for (index,name) in names.iter().enumerate() {
+            if let Some(name) = name {
+                let mut slot = map_hash(name.as_bytes()) as usize & (capacity-1);
+                while slots[slot] != 0 {
+                    slot = (slot+1) & (capacity-1);
+                }
+                slots[slot] = index as u64 + 1;
+            }
+        }
+        let names_len = self.names.keys().map(|x| x.len()).sum::<usize>();
+        var.write_u64::<B>((NAME_FIELD_SIZE*(1 + capacity + self.types.len()) + names_len) as u64).unwrap();
+        var.write_u64::<B>(capacity as u64).unwrap();
+        for slot in slots {
+            var.write_u64::<B>(slot).unwrap();
+        }
+        let mut end = 0;
+        for name in &names {
+            end += name.map_or(0, |x| x.len());
+            var.write_u64::<B>(end as u64).unwrap();
+        }
+        for name in names.iter().flatten() {
+            var.extend_from_slice(name.as_bytes());
+        }
+    }
+
+    ///Computes the offset of every payload relative to the end of a header with positions of the
+    ///given size and a schema block of the given length, returns the offsets and the total length
+    ///of the payload
+    fn layout(&self, position_size: usize, schema_len: usize) -> (Vec<usize>,usize) {
+        let header_len = self.header_len(position_size, schema_len);
+        let mut offsets = Vec::with_capacity(self.types.len());
+        let mut offset = 0;
+        for (alignment,data) in self.alignments.iter().zip(&self.data) {
+            offset += (alignment - (header_len + offset) % alignment) % alignment;
+            offsets.push(offset);
+            offset += data.len();
+        }
+        (offsets,offset)
+    }
+
+    ///Returns the first entry which ends past the largest 32 bit offset if the buffer is written
+    ///with 32 bit offsets and a schema block of the given length
+    fn overflowing_key(&self, schema_len: usize) -> Option<usize> {
+        let (offsets,_) = self.layout(position_size(self.header_flags(false)), schema_len);
+        #[cfg(not(test))]
+        let max_offset = MAX_OFFSET_32;
+        #[cfg(test)]
+        let max_offset = self.max_offset_32;
+        offsets.iter().zip(&self.data).position(|(offset,data)| offset + data.len() > max_offset)
+    }
+
+    ///Writes the header with 32 or 64 bit offsets and the given schema block followed by the
+    ///padded payloads
+    fn intern_finalize(&self, wide: bool, schema: &[u8]) -> Vec<u8> {
+        let flags = self.header_flags(wide);
+        let position_size = position_size(flags);
+        let (offsets,payload_len) = self.layout(position_size, schema.len());
+
+        let header_len = self.header_len(position_size, schema.len());
+        let mut var: Vec<u8> = Vec::with_capacity(header_len + payload_len);
+        var.extend_from_slice(MAGIC);
+        var.write_u16::<LittleEndian>(FORMAT_VERSION).unwrap();
+        var.write_u16::<LittleEndian>(0).unwrap();
+        var.write_u32::<LittleEndian>(flags).unwrap();
+        var.write_i32::<B>(self.types.len() as i32).unwrap();
+        //Placeholder for the checksum of the header which is filled in after the positions
+        var.write_u32::<B>(0).unwrap();
+        for (val,&offset) in offsets.iter().enumerate() {
+            let end = offset + self.data[val].len();
+            if wide {
+                var.write_u64::<B>(offset as u64).unwrap();
+                var.write_u64::<B>(end as u64).unwrap();
+            } else {
+                var.write_i32::<B>(offset as i32).unwrap();
+                var.write_i32::<B>(end as i32).unwrap();
+            }
+            var.write_i32::<B>(self.types[val]).unwrap();
+            if self.checksums {
+                var.write_u32::<B>(crc32c::crc32c(&self.data[val])).unwrap();
+            }
+        }
+        if !self.names.is_empty() {
+            self.write_names(&mut var);
+        }
+        if self.schema {
+            var.write_u64::<B>(schema.len() as u64).unwrap();
+            var.extend_from_slice(schema);
+        }
+        let crc = crc32c::crc32c(&var[..PREFIX_SIZE+4]);
+        let crc = crc32c::crc32c_append(crc, &var[PREFIX_SIZE+8..]);
+        B::write_u32(&mut var[PREFIX_SIZE+4..PREFIX_SIZE+8], crc);
+        for (x,offset) in self.data.iter().zip(offsets) {
+            var.resize(header_len + offset, 0);
+            var.extend_from_slice(x);
+        }
+        var
+    }
+}
+
+
+
+#[cfg(test)]
+//The oldest tests are kept in the form they were written in
+#[allow(clippy::useless_vec, clippy::bool_assert_comparison, clippy::redundant_slicing, clippy::needless_as_bytes)]
+mod tests {
+    use super::{MemBufferWriter,MemBufferReader,MemBufferError,MemBufferTypes,MemBufferSerialize};
+    use super::{FORMAT_VERSION,LEGACY_FORMAT_VERSION,FLAG_ALIGNED,FLAG_BIG_ENDIAN,FLAG_CHECKSUMS,FLAG_COMPRESSED,FLAG_OFFSETS_64,FLAG_NAMES,FLAG_SCHEMA};
+    use super::{OffsetWidth,MemBufferPod,StrList,BytesList};
+    use super::{SortedU64Set,SortedBytesSet,SortedU64Map,SortedBytesMap,U64Keys,Field,FieldInfo,Schema,BreakingChange,check_compatibility,EntrySchema};
+    #[cfg(feature = "bincode")]
+    use serde::{Serialize,Deserialize};
+    use std::borrow::Cow;
+    use byteorder::{BigEndian,LittleEndian,NativeEndian,ByteOrder};
+
+    //Offset of the first header entry in a buffer created by the writer
+    const POSITIONS_START: usize = 20;
+
+    //Recomputes the checksum of the header after it was modified on purpose, the buffer has to
+    //be written without entry checksums
+    fn reseal_header(buffer: &mut [u8], entries: usize) {
+        let crc = crc32c::crc32c(&buffer[..16]);
+        let crc = crc32c::crc32c_append(crc, &buffer[POSITIONS_START..POSITIONS_START+entries*12]);
+        buffer[16..20].copy_from_slice(&crc.to_le_bytes());
+    }
+
+    #[cfg(feature = "bincode")]
+    #[derive(Serialize,Deserialize)]
+    struct HeavyStruct {
+        vec: Vec<u64>,
+        name: String,
+        frequency: i32,
+        id: i32,
+    }
+
+    #[test]
+    fn check_enum_usage() {
+        let mut writer = MemBufferWriter::new();
+        writer.add_entry("Der moderne Prometheus");
+        writer.add_entry("Dies hier ist nur ein Satz");
+        writer.add_entry::<&[u64]>(&vec![0,1,2,3,4,5]);
+
+        let result = writer.finalize().unwrap();
+
+        let reader = MemBufferReader::new(&result).unwrap();
+
+        let _: &str = reader.load_entry(0).unwrap();
+        let _: &str = reader.load_entry(1).unwrap();
+        let _: &[u64] = reader.load_entry(2).unwrap();
+    }
+
+    macro_rules! write_primitives {
+        ($writer:expr) => {
+            $writer.add_entry(true);
+            $writer.add_entry('ß');
+            $writer.add_entry(200u8);
+            $writer.add_entry(60_000u16);
+            $writer.add_entry(4_000_000_000u32);
+            $writer.add_entry(u64::MAX-1);
+            $writer.add_entry(u128::MAX-2);
+            $writer.add_entry(-100i8);
+            $writer.add_entry(-30_000i16);
+            $writer.add_entry(-2_000_000_000i32);
+            $writer.add_entry(i64::MIN+1);
+            $writer.add_entry(i128::MIN+2);
+            $writer.add_entry(1.5f32);
+            $writer.add_entry(-2.25f64);
+        };
+    }
+
+    macro_rules! check_primitives {
+        ($reader:expr) => {
+            assert_eq!($reader.load_entry::<bool>(0).unwrap(), true);
+            assert_eq!($reader.load_entry::<char>(1).unwrap(), 'ß');
+            assert_eq!($reader.load_entry::<u8>(2).unwrap(), 200);
+            assert_eq!($reader.load_entry::<u16>(3).unwrap(), 60_000);
+            assert_eq!($reader.load_entry::<u32>(4).unwrap(), 4_000_000_000);
+            assert_eq!($reader.load_entry::<u64>(5).unwrap(), u64::MAX-1);
+            assert_eq!($reader.load_entry::<u128>(6).unwrap(), u128::MAX-2);
+            assert_eq!($reader.load_entry::<i8>(7).unwrap(), -100);
+            assert_eq!($reader.load_entry::<i16>(8).unwrap(), -30_000);
+            assert_eq!($reader.load_entry::<i32>(9).unwrap(), -2_000_000_000);
+            assert_eq!($reader.load_entry::<i64>(10).unwrap(), i64::MIN+1);
+            assert_eq!($reader.load_entry::<i128>(11).unwrap(), i128::MIN+2);
+            assert_eq!($reader.load_entry::<f32>(12).unwrap(), 1.5);
+            assert_eq!($reader.load_entry::<f64>(13).unwrap(), -2.25);
+            assert!($reader.load_entry::<u32>(0).is_err());
+            assert!($reader.load_entry::<i64>(5).is_err());
+        };
+    }
+
+    #[test]
+    fn check_primitive_types() {
+        let mut writer = MemBufferWriter::new();
+        write_primitives!(writer);
+        let result = writer.finalize().unwrap();
+        check_primitives!(MemBufferReader::new(&result).unwrap());
+
+        let mut writer = MemBufferWriter::<BigEndian>::with_byte_order();
+        write_primitives!(writer);
+        let result = writer.finalize().unwrap();
+        check_primitives!(MemBufferReader::<BigEndian>::with_byte_order(&result).unwrap());
+    }
+
+    #[test]
+    fn check_invalid_bool_and_char() {
+        let mut writer = MemBufferWriter::new();
+        writer.set_checksums(false);
+        writer.add_entry(2u8);
+        writer.add_entry(0xD800u32);
+        let mut result = writer.finalize().unwrap();
+
+        //Retype the entries to bool and char
+        for (key,value_type) in [MemBufferTypes::Bool as i32, MemBufferTypes::Char as i32].iter().enumerate() {
+            let start = POSITIONS_START + key*12 + 8;
+            result[start..start+4].copy_from_slice(&value_type.to_le_bytes());
+        }
+        reseal_header(&mut result, 2);
+        let reader = MemBufferReader::new(&result).unwrap();
+        assert!(matches!(reader.load_entry::<bool>(0), Err(MemBufferError::InvalidValue { type_name: "bool" })));
+        assert!(matches!(reader.load_entry::<char>(1), Err(MemBufferError::InvalidValue { type_name: "char" })));
+    }
+
+    #[test]
+    fn check_primitive_slices() {
+        let mut writer = MemBufferWriter::new();
+        writer.add_entry("odd");
+        writer.add_entry::<&[i8]>(&[-1,2]);
+        writer.add_entry::<&[u16]>(&[1,60_000]);
+        writer.add_entry::<&[i16]>(&[-1,30_000]);
+        writer.add_entry::<&[i32]>(&[-1,7]);
+        writer.add_entry::<&[i64]>(&[i64::MIN,7]);
+        writer.add_entry::<&[u128]>(&[u128::MAX,7]);
+        writer.add_entry::<&[i128]>(&[i128::MIN,7]);
+        writer.add_entry::<&[f32]>(&[1.5,-0.5]);
+        writer.add_entry::<&[f64]>(&[2.5,-1e300]);
+        let result = writer.finalize().unwrap();
+
+        let reader = MemBufferReader::new(&result).unwrap();
+        assert_eq!(reader.load_entry::<&[i8]>(1).unwrap(), &[-1,2]);
+        assert_eq!(reader.load_entry::<&[u16]>(2).unwrap(), &[1,60_000]);
+        assert_eq!(reader.load_entry::<&[i16]>(3).unwrap(), &[-1,30_000]);
+        assert_eq!(reader.load_entry::<&[i32]>(4).unwrap(), &[-1,7]);
+        assert_eq!(reader.load_entry::<&[i64]>(5).unwrap(), &[i64::MIN,7]);
+        assert_eq!(reader.load_entry::<&[u128]>(6).unwrap(), &[u128::MAX,7]);
+        assert_eq!(reader.load_entry::<&[i128]>(7).unwrap(), &[i128::MIN,7]);
+        assert_eq!(reader.load_entry::<&[f32]>(8).unwrap(), &[1.5,-0.5]);
+        assert_eq!(reader.load_entry::<&[f64]>(9).unwrap(), &[2.5,-1e300]);
+        assert!(reader.load_entry::<&[u64]>(5).is_err());
+        assert!(reader.load_entry::<&[i32]>(8).is_err());
+
+        let mut writer = MemBufferWriter::<BigEndian>::with_byte_order();
+        writer.add_entry::<&[i8]>(&[-1,2]);
+        writer.add_entry::<&[f64]>(&[2.5,-1e300]);
+        writer.add_entry::<Cow<[i128]>>(Cow::Borrowed(&[i128::MIN,7]));
+        let result = writer.finalize().unwrap();
+
+        let reader = MemBufferReader::<BigEndian>::with_byte_order(&result).unwrap();
+        assert_eq!(reader.load_entry::<&[i8]>(0).unwrap(), &[-1,2]);
+        assert_eq!(&reader.load_entry::<Cow<[f64]>>(1).unwrap()[..], &[2.5,-1e300]);
+        assert_eq!(&reader.load_entry::<Cow<[i128]>>(2).unwrap()[..], &[i128::MIN,7]);
+    }
+
+    #[test]
+    fn check_owned_values() {
+        let mut writer = MemBufferWriter::<BigEndian>::with_byte_order();
+        writer.add_entry(String::from("Earth"));
+        writer.add_entry(vec![1u8,2,3]);
+        writer.add_entry(vec![1u64,u64::MAX]);
+        writer.add_entry(vec![-1.5f32,2.5].into_boxed_slice());
+        writer.add_entry::<Cow<[u8]>>(Cow::Owned(vec![4,5]));
+        let result = writer.finalize().unwrap();
+
+        let owned = {
+            let reader = MemBufferReader::<BigEndian>::with_byte_order(&result).unwrap();
+            assert_eq!(reader.load_entry::<&str>(0).unwrap(), "Earth");
+            assert!(matches!(reader.load_entry::<Cow<[u8]>>(4).unwrap(), Cow::Borrowed(&[4,5])));
+            (reader.load_entry::<String>(0).unwrap(),
+             reader.load_entry::<Vec<u8>>(1).unwrap(),
+             reader.load_entry::<Vec<u64>>(2).unwrap(),
+             reader.load_entry::<Box<[f32]>>(3).unwrap())
+        };
+        drop(result);
+        assert_eq!(owned.0, "Earth");
+        assert_eq!(owned.1, vec![1,2,3]);
+        assert_eq!(owned.2, vec![1,u64::MAX]);
+        assert_eq!(&owned.3[..], &[-1.5,2.5]);
+
+        //Misaligned entries cannot be borrowed but are copied into owned values
+        let mut writer = MemBufferWriter::new();
+        writer.add_entry(vec![1u32,2,3]);
+        let result = writer.finalize().unwrap();
+        let mut shifted = vec![0u8; result.len()+1];
+        shifted[1..].copy_from_slice(&result);
+        let reader = MemBufferReader::new(&shifted[1..]).unwrap();
+        assert!(reader.load_entry::<&[u32]>(0).is_err());
+        assert_eq!(reader.load_entry::<Vec<u32>>(0).unwrap(), vec![1,2,3]);
+        assert!(reader.load_entry::<Vec<u64>>(0).is_err());
+    }
+
+    #[test]
+    fn check_option_entries() {
+        let mut writer = MemBufferWriter::new();
+        writer.add_entry(Some("Earth"));
+        writer.add_entry::<Option<&str>>(None);
+        writer.add_entry(&[0u8;0][..]);
+        writer.add_entry::<Option<&[u64]>>(Some(&[1,2]));
+        writer.add_entry(Some(1));
+        writer.set_entry::<Option<i32>>(None, 4).unwrap();
+        assert_eq!(writer.load_entry::<Option<i32>>(4).unwrap(), None);
+        assert!(writer.load_entry::<i32>(4).is_err());
+        let result = writer.finalize().unwrap();
+
+        let reader = MemBufferReader::new(&result).unwrap();
+        assert_eq!(reader.load_entry::<Option<&str>>(0).unwrap(), Some("Earth"));
+        assert_eq!(reader.load_entry::<&str>(0).unwrap(), "Earth");
+        assert_eq!(reader.load_entry::<Option<&str>>(1).unwrap(), None);
+        assert_eq!(reader.load_entry::<Option<u64>>(1).unwrap(), None);
+        assert!(reader.is_null(1).unwrap());
+        assert!(matches!(reader.load_entry::<&str>(1), Err(MemBufferError::FieldTypeError(x,y)) if x == MemBufferTypes::Null as i32 && y == MemBufferTypes::Text as i32));
+
+        //An empty value is not null
+        assert!(!reader.is_null(2).unwrap());
+        assert_eq!(reader.load_entry::<Option<&[u8]>>(2).unwrap(), Some(&[][..]));
+        assert!(matches!(reader.load_entry::<Option<&str>>(2), Err(MemBufferError::FieldTypeError(_,_))));
+        assert_eq!(reader.load_entry::<Option<&[u64]>>(3).unwrap(), Some(&[1,2][..]));
+        assert_eq!(reader.load_entry::<Option<i32>>(4).unwrap(), None);
+        assert!(reader.is_null(5).is_err());
+    }
+
+    #[test]
+    fn check_lists() {
+        let names = [String::from("Hello"), String::new(), String::from("визитной")];
+        let blobs: Vec<&[u8]> = vec![&[1,2,3], &[], &[4]];
+        let mut writer = MemBufferWriter::<BigEndian>::with_byte_order();
+        writer.add_entry(&names[..]);
+        writer.add_entry(&blobs[..]);
+        writer.add_entry::<&[&str]>(&[]);
+        let result = writer.finalize().unwrap();
+
+        let reader = MemBufferReader::<BigEndian>::with_byte_order(&result).unwrap();
+        let list: StrList = reader.load_entry(0).unwrap();
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.get(2).unwrap(), "визитной");
+        assert_eq!(list.iter().collect::<Result<Vec<_>,_>>().unwrap(), vec!["Hello","","визитной"]);
+        assert!(matches!(list.get(3), Err(MemBufferError::KeyOutOfRange { key: 3, len: 3 })));
+        let bytes: BytesList = reader.load_entry(1).unwrap();
+        assert_eq!(bytes.iter().collect::<Result<Vec<_>,_>>().unwrap(), blobs);
+        assert!(reader.load_entry::<StrList>(2).unwrap().is_empty());
+        assert!(reader.load_entry::<BytesList>(0).is_err());
+
+        //Views can be stored again in another byte order
+        let mut writer = MemBufferWriter::new();
+        writer.add_entry(list);
+        writer.add_entry(bytes);
+        let result = writer.finalize().unwrap();
+        let reader = MemBufferReader::new(&result).unwrap();
+        assert_eq!(reader.load_entry::<StrList>(0).unwrap().get(0).unwrap(), "Hello");
+        assert_eq!(reader.load_entry::<BytesList>(1).unwrap().get(2).unwrap(), &[4]);
+    }
+
+    #[test]
+    fn check_corrupt_lists() {
+        let mut writer = MemBufferWriter::new();
+        writer.set_checksums(false);
+        writer.add_entry(&["Earth","Moon"][..]);
+        let mut result = writer.finalize().unwrap();
+        let start = result.len() - "EarthMoon".len() - 3*8;
+
+        //First end offset past the data and the second element is not on a char boundary
+        let mut corrupt = result.clone();
+        corrupt[start+8..start+16].copy_from_slice(&100u64.to_le_bytes());
+        let reader = MemBufferReader::new(&corrupt).unwrap();
+        let list: StrList = reader.load_entry(0).unwrap();
+        assert!(matches!(list.get(0), Err(MemBufferError::InvalidPosition { key: 0, .. })));
+        assert!(list.get(1).is_err());
+
+        //Count which does not fit into the entry
+        result[start..start+8].copy_from_slice(&u64::MAX.to_le_bytes());
+        let reader = MemBufferReader::new(&result).unwrap();
+        assert!(matches!(reader.load_entry::<StrList>(0), Err(MemBufferError::TruncatedList { .. })));
+    }
+
+    #[test]
+    fn check_map() {
+        let keys: Vec<String> = (0..1000).map(|x| format!("key{}",x)).collect();
+        let mut writer = MemBufferWriter::<BigEndian>::with_byte_order();
+        writer.add_entry("odd");
+        writer.add_map(keys.iter().enumerate().map(|(x,key)| (key, x as u64))).unwrap();
+        writer.add_map(vec![(&b"first"[..], &[1u64,2][..]), (&b"second"[..], &[3u64][..])]).unwrap();
+        writer.add_map(Vec::<(&str,i32)>::new()).unwrap();
+        let result = writer.finalize().unwrap();
+
+        let reader = MemBufferReader::<BigEndian>::with_byte_order(&result).unwrap();
+        let map = reader.load_map(1).unwrap();
+        assert_eq!(map.len(), 1000);
+        for (x,key) in keys.iter().enumerate() {
+            assert_eq!(map.get_as::<u64,_>(key).unwrap(), Some(x as u64));
+        }
+        assert_eq!(map.get_as::<u64,_>("key1000").unwrap(), None);
+        assert!(!map.contains_key("").unwrap());
+        assert!(map.get_as::<i32,_>("key1").is_err());
+        let (key,_) = map.iter().nth(10).unwrap().unwrap();
+        assert_eq!(key, b"key10");
+
+        let map = reader.load_map(2).unwrap();
+        assert_eq!(&map.get_as::<Cow<[u64]>,_>(b"first").unwrap().unwrap()[..], &[1,2]);
+        assert_eq!(&map.get_as::<Cow<[u64]>,_>("second").unwrap().unwrap()[..], &[3]);
+        assert!(reader.load_map(3).unwrap().is_empty());
+        assert_eq!(reader.load_map(3).unwrap().get("first").unwrap(), None);
+        assert!(reader.load_map(0).is_err());
+
+        //Values are aligned for zero copy loading in the native byte order
+        let mut writer = MemBufferWriter::new();
+        writer.add_entry("odd");
+        writer.add_map(vec![("a", &[1u64][..]), ("bcd", &[2u64,3][..])]).unwrap();
+        let result = writer.finalize().unwrap();
+        let reader = MemBufferReader::new(&result).unwrap();
+        assert_eq!(reader.load_map(1).unwrap().get_as::<&[u64],_>("bcd").unwrap(), Some(&[2u64,3][..]));
+    }
+
+    #[test]
+    fn check_map_errors() {
+        let mut writer = MemBufferWriter::new();
+        assert!(matches!(writer.add_map(vec![("a",1),("b",2),("a",3)]), Err(MemBufferError::DuplicateKey { index: 2 })));
+        //None could not be told apart from an empty value when loading
+        assert!(matches!(writer.add_map(vec![("a",Some(1u64)),("b",None)]), Err(MemBufferError::NullableMapValue)));
+        assert!(matches!(writer.add_sorted_map_u64(vec![(1,Some("a"))]), Err(MemBufferError::NullableMapValue)));
+        assert!(matches!(writer.add_sorted_map_bytes(vec![("a",None::<u64>)]), Err(MemBufferError::NullableMapValue)));
+        assert!(writer.is_empty());
+
+        writer.set_checksums(false);
+        writer.add_map(vec![("a",1),("b",2)]).unwrap();
+        let mut result = writer.finalize().unwrap();
+        let reader = MemBufferReader::new(&result).unwrap();
+        let start = result.len() - reader.payload_len();
+
+        //Point every slot to a pair which does not exist
+        let capacity = LittleEndian::read_u64(&result[start+8..]) as usize;
+        for slot in 0..capacity {
+            let raw = start + 24 + slot*16;
+            let hash = crc32c::crc32c(b"c") as u64;
+            result[raw..raw+8].copy_from_slice(&hash.to_le_bytes());
+            result[raw+8..raw+16].copy_from_slice(&5u64.to_le_bytes());
+        }
+        let reader = MemBufferReader::new(&result).unwrap();
+        let map = reader.load_map(0).unwrap();
+        assert!(matches!(map.get("c"), Err(MemBufferError::InvalidMap)));
+        assert_eq!(map.get("a").unwrap(), None);
+    }
+
+    #[test]
+    fn check_sorted_sets() {
+        let mut writer = MemBufferWriter::new();
+        writer.add_sorted_set_u64(vec![40,10,30,10,20]);
+        writer.add_sorted_set_bytes(vec!["pear","apple","fig","apple"]);
+        writer.add_sorted_set_u64(Vec::new());
+        let result = writer.finalize().unwrap();
+
+        let reader = MemBufferReader::new(&result).unwrap();
+        let set: SortedU64Set = reader.load_sorted_set(0).unwrap();
+        assert_eq!(set.len(), 4);
+        assert_eq!(set.keys().as_slice().unwrap(), &[10,20,30,40]);
+        assert!(set.contains(30).unwrap());
+        assert!(!set.contains(35).unwrap());
+        assert!(!set.contains(50).unwrap());
+        assert_eq!(set.rank(0).unwrap(), 0);
+        assert_eq!(set.rank(30).unwrap(), 2);
+        assert_eq!(set.rank(100).unwrap(), 4);
+        let range = |r: Vec<Result<u64,MemBufferError>>| r.into_iter().collect::<Result<Vec<_>,_>>().unwrap();
+        assert_eq!(range(set.range(20..40).unwrap().collect()), vec![20,30]);
+        assert_eq!(range(set.range(20..=40).unwrap().collect()), vec![20,30,40]);
+        assert_eq!(range(set.range(15..16).unwrap().collect()), Vec::<u64>::new());
+        assert_eq!(range(set.range(..).unwrap().collect()), vec![10,20,30,40]);
+        assert_eq!(range(set.range(30..).unwrap().collect()), vec![30,40]);
+
+        let set: SortedBytesSet = reader.load_sorted_set(1).unwrap();
+        let keys: Vec<&[u8]> = set.iter().map(|x| x.unwrap()).collect();
+        assert_eq!(keys, vec![&b"apple"[..],&b"fig"[..],&b"pear"[..]]);
+        assert!(set.contains(&b"fig"[..]).unwrap());
+        assert!(!set.contains(&b"figs"[..]).unwrap());
+        assert_eq!(set.rank(&b"b"[..]).unwrap(), 1);
+
+        let set: SortedU64Set = reader.load_sorted_set(2).unwrap();
+        assert!(set.is_empty());
+        assert!(!set.contains(1).unwrap());
+
+        assert!(matches!(reader.load_sorted_set::<BytesList>(0), Err(MemBufferError::FieldTypeError(_,_))));
+        assert!(matches!(reader.load_sorted_map::<U64Keys>(0), Err(MemBufferError::FieldTypeError(_,_))));
+    }
+
+    #[test]
+    fn check_sorted_maps() {
+        let mut writer = MemBufferWriter::<BigEndian>::with_byte_order();
+        writer.add_sorted_map_u64(vec![(7,"seven"),(3,"three"),(11,"eleven")]).unwrap();
+        writer.add_sorted_map_bytes(vec![("b",2u64),("a",1),("c",3)]).unwrap();
+        assert!(matches!(writer.add_sorted_map_u64(vec![(1,1u8),(2,2),(1,3)]), Err(MemBufferError::DuplicateKey { index: 2 })));
+        assert!(matches!(writer.add_sorted_map_bytes(vec![("x",1u8),("x",2)]), Err(MemBufferError::DuplicateKey { index: 1 })));
+        let result = writer.finalize().unwrap();
+
+        let reader = MemBufferReader::<BigEndian>::with_byte_order(&result).unwrap();
+        assert_eq!(reader.len(), 2);
+        let map: SortedU64Map = reader.load_sorted_map(0).unwrap();
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get_as::<&str,_>(7).unwrap(), Some("seven"));
+        assert_eq!(map.get_as::<&str,_>(8).unwrap(), None);
+        assert!(matches!(map.get_as::<u64,_>(7), Err(MemBufferError::FieldTypeError(_,_))));
+        assert!(matches!(map.keys().keys().as_slice(), Err(MemBufferError::WrongByteOrder { big_endian: true })));
+        let pairs: Vec<(u64,&[u8])> = map.range(4..).unwrap().map(|x| x.unwrap()).collect();
+        assert_eq!(pairs, vec![(7,&b"seven"[..]),(11,&b"eleven"[..])]);
+
+        let map: SortedBytesMap = reader.load_sorted_map(1).unwrap();
+        assert_eq!(map.get_as::<u64,_>(&b"b"[..]).unwrap(), Some(2));
+        assert_eq!(map.get(&b"d"[..]).unwrap(), None);
+        let keys: Vec<&[u8]> = map.iter().map(|x| x.unwrap().0).collect();
+        assert_eq!(keys, vec![&b"a"[..],&b"b"[..],&b"c"[..]]);
+        //Values are aligned for zero copy access
+        assert_eq!(map.value(2).unwrap().as_ptr() as usize % std::mem::align_of::<u64>(), 0);
+
+        let mut native = MemBufferWriter::new();
+        native.add_sorted_map_u64(vec![(1,&[1u64,2][..]),(2,&[3u64][..])]).unwrap();
+        let result = native.finalize().unwrap();
+        let reader = MemBufferReader::new(&result).unwrap();
+        let map: SortedU64Map = reader.load_sorted_map(0).unwrap();
+        assert_eq!(map.get_as::<&[u64],_>(2).unwrap(), Some(&[3u64][..]));
+    }
+
+    #[test]
+    fn check_named_entries() {
+        let mut writer = MemBufferWriter::new();
+        writer.add_entry("unnamed");
+        writer.add_named_entry("title", "Membuffer").unwrap();
+        writer.add_named_entry("id", 42u64).unwrap();
+        writer.add_sorted_set_u64(vec![3,1,2]);
+        writer.name_entry(3, "ids").unwrap();
+        writer.add_named_entry("a", &[1u64,2][..]).unwrap();
+        assert!(matches!(writer.add_named_entry("id", 1u8), Err(MemBufferError::DuplicateName { .. })));
+        assert!(matches!(writer.name_entry(0, "title"), Err(MemBufferError::DuplicateName { .. })));
+        assert!(matches!(writer.name_entry(5, "other"), Err(MemBufferError::KeyOutOfRange { key: 5, len: 5 })));
+        assert_eq!(writer.len(), 5);
+        assert_eq!(writer.index_of("id"), Some(2));
+        let result = writer.finalize().unwrap();
+
+        let reader = MemBufferReader::new(&result).unwrap();
+        assert_eq!(reader.flags() & FLAG_NAMES, FLAG_NAMES);
+        assert_eq!(reader.len(), 5);
+        assert_eq!(reader.load_entry::<&str>(0).unwrap(), "unnamed");
+        //Payloads stay aligned behind the name table
+        assert_eq!(reader.load_named_entry::<&[u64]>("a").unwrap(), &[1,2]);
+        assert_eq!(reader.load_named_entry::<&str>("title").unwrap(), "Membuffer");
+        assert_eq!(reader.load_named_entry::<u64>("id").unwrap(), 42);
+        assert!(matches!(reader.load_named_entry::<&str>("id"), Err(MemBufferError::FieldTypeError(_,_))));
+        assert!(matches!(reader.load_named_entry::<&str>("unnamed"), Err(MemBufferError::UnknownName { .. })));
+        assert_eq!(reader.index_of(""), None);
+        let set: SortedU64Set = reader.load_sorted_set(reader.index_of("ids").unwrap()).unwrap();
+        assert_eq!(set.len(), 3);
+        let mut names: Vec<(&str,usize)> = reader.names().collect();
+        names.sort_unstable();
+        assert_eq!(names, vec![("a",4),("id",2),("ids",3),("title",1)]);
+
+        //Names survive reloading the buffer and renaming entries
+        let mut writer = MemBufferWriter::from(&result).unwrap();
+        writer.name_entry(1, "name").unwrap();
+        writer.add_named_entry("title", "Renamed").unwrap();
+        let result = writer.finalize().unwrap();
+        let reader = MemBufferReader::new(&result).unwrap();
+        assert_eq!(reader.load_named_entry::<&str>("name").unwrap(), "Membuffer");
+        assert_eq!(reader.load_named_entry::<&str>("title").unwrap(), "Renamed");
+        assert_eq!(reader.load_named_entry::<u64>("id").unwrap(), 42);
+
+        //Buffers without names do not carry a name table
+        let mut writer = MemBufferWriter::new();
+        writer.add_entry(1u8);
+        let result = writer.finalize().unwrap();
+        let reader = MemBufferReader::new(&result).unwrap();
+        assert_eq!(reader.flags() & FLAG_NAMES, 0);
+        assert_eq!(reader.index_of("id"), None);
+        assert_eq!(reader.names().count(), 0);
+    }
+
+    #[test]
+    fn check_many_named_entries() {
+        let mut writer = MemBufferWriter::<BigEndian>::with_byte_order();
+        for x in 0..3000u64 {
+            writer.add_named_entry(&format!("key{}",x), x).unwrap();
+        }
+        let result = writer.finalize().unwrap();
+        let reader = MemBufferReader::<BigEndian>::with_byte_order(&result).unwrap();
+        for x in (0..3000u64).step_by(7) {
+            assert_eq!(reader.index_of(&format!("key{}",x)), Some(x as usize));
+            assert_eq!(reader.load_named_entry::<u64>(&format!("key{}",x)).unwrap(), x);
+        }
+        assert_eq!(reader.index_of("key3000"), None);
+    }
+
+    #[test]
+    fn check_corrupt_names() {
+        let mut writer = MemBufferWriter::new();
+        writer.set_checksums(false);
+        writer.add_named_entry("a", 1u8).unwrap();
+        writer.add_named_entry("b", 2u8).unwrap();
+        let result = writer.finalize().unwrap();
+        let names_start = POSITIONS_START + 2*12;
+        let names_len = LittleEndian::read_u64(&result[names_start..]) as usize;
+        let header_end = names_start + 8 + names_len;
+        let reseal = |buffer: &mut Vec<u8>| {
+            let crc = crc32c::crc32c(&buffer[..16]);
+            let crc = crc32c::crc32c_append(crc, &buffer[POSITIONS_START..header_end]);
+            buffer[16..20].copy_from_slice(&crc.to_le_bytes());
+        };
+
+        //A slot pointing past the last entry
+        let mut corrupt = result.clone();
+        corrupt[names_start+16..names_start+24].copy_from_slice(&7u64.to_le_bytes());
+        reseal(&mut corrupt);
+        assert!(matches!(MemBufferReader::new(&corrupt), Err(MemBufferError::InvalidNameTable)));
+
+        //A capacity which is not a power of two
+        let mut corrupt = result.clone();
+        corrupt[names_start+8..names_start+16].copy_from_slice(&3u64.to_le_bytes());
+        reseal(&mut corrupt);
+        assert!(matches!(MemBufferReader::new_unchecked(&corrupt), Err(MemBufferError::InvalidNameTable)));
+
+        //A name which ends past the names
+        let mut corrupt = result.clone();
+        corrupt[header_end-10..header_end-2].copy_from_slice(&9u64.to_le_bytes());
+        reseal(&mut corrupt);
+        assert!(matches!(MemBufferReader::new(&corrupt), Err(MemBufferError::InvalidNameTable)));
+        let reader = MemBufferReader::new_unchecked(&corrupt).unwrap();
+        assert_eq!(reader.index_of("b"), None);
+
+        //The name table is covered by the checksum of the header
+        let mut corrupt = result.clone();
+        corrupt[header_end-1] = b'c';
+        assert!(matches!(MemBufferReader::new(&corrupt), Err(MemBufferError::HeaderChecksumMismatch)));
+    }
+
+    struct Article;
+
+    impl Article {
+        const TITLE: Field<&'static str,Article> = Field::new(0);
+        const VIEWS: Field<u64,Article> = Field::new(1);
+        const TAGS: Field<Option<&'static [u32]>,Article> = Field::new(2);
+    }
+
+    impl Schema for Article {
+        fn fields() -> Vec<FieldInfo> {
+            vec![Article::TITLE.info(), Article::VIEWS.info(), Article::TAGS.info()]
+        }
+    }
+
+    #[test]
+    fn check_schema_block() {
+        let mut child = MemBufferWriter::new();
+        child.set_schema(true);
+        child.add_named_entry("id", 7u32).unwrap();
+        let mut writer = MemBufferWriter::new();
+        writer.set_schema(true);
+        writer.add_named_entry("title", "Membuffer").unwrap();
+        writer.add_entry(&[1.5f32][..]);
+        writer.add_map(vec![("a",1u64)]).unwrap();
+        writer.add_sorted_map_u64(vec![(1,"one")]).unwrap();
+        writer.add_named_entry("child", child).unwrap();
+        writer.add_entry(MemBufferWriter::new());
+        writer.register_pod::<Record>();
+        writer.register_type(-1, "Custom");
+        let result = writer.finalize().unwrap();
+
+        let reader = MemBufferReader::new(&result).unwrap();
+        assert_eq!(reader.flags() & FLAG_SCHEMA, FLAG_SCHEMA);
+        assert_eq!(reader.load_named_entry::<&str>("title").unwrap(), "Membuffer");
+        let schema = reader.schema().unwrap().unwrap();
+        assert_eq!(schema.len(), 6);
+        assert_eq!(schema.entry(0).unwrap(), EntrySchema { name: Some("title"), type_id: MemBufferTypes::Text.into(), type_name: Some("text"), element_type: None });
+        assert_eq!(schema.entry(1).unwrap(), EntrySchema { name: None, type_id: MemBufferTypes::VectorF32.into(), type_name: Some("[f32]"), element_type: Some("f32") });
+        assert_eq!(schema.entry(2).unwrap().element_type, Some("u64"));
+        assert_eq!(schema.entry(3).unwrap().type_name, Some("sorted-map-u64"));
+        assert_eq!(schema.entry(3).unwrap().element_type, Some("text"));
+        assert!(matches!(schema.entry(6), Err(MemBufferError::KeyOutOfRange { key: 6, len: 6 })));
+
+        //Nested buffers carry their own schema
+        let nested = schema.nested(4).unwrap().unwrap();
+        assert_eq!(nested.entry(0).unwrap().name, Some("id"));
+        assert_eq!(nested.entry(0).unwrap().type_name, Some("u32"));
+        assert!(schema.nested(5).unwrap().is_none());
+        assert!(schema.nested(0).unwrap().is_none());
+
+        let registry: Vec<(i32,&str)> = schema.registry().map(|x| x.unwrap()).collect();
+        assert_eq!(registry.len(), 2);
+        assert!(registry.contains(&(-1,"Custom")));
+        assert_eq!(schema.type_name(<&Record>::get_mem_buffer_type()).unwrap(), Some("Record"));
+        assert_eq!(schema.type_name(MemBufferTypes::Bool.into()).unwrap(), Some("bool"));
+        assert_eq!(schema.type_name(12345).unwrap(), None);
+
+        //The schema and the registry survive reloading the buffer
+        let mut writer = MemBufferWriter::from(&result).unwrap();
+        writer.add_entry(true);
+        let result = writer.finalize().unwrap();
+        let reader = MemBufferReader::new(&result).unwrap();
+        let schema = reader.schema().unwrap().unwrap();
+        assert_eq!(schema.len(), 7);
+        assert_eq!(schema.entry(4).unwrap().name, Some("child"));
+        assert_eq!(schema.entry(6).unwrap().type_name, Some("bool"));
+        assert_eq!(schema.type_name(-1).unwrap(), Some("Custom"));
+
+        //Buffers are written without schema by default
+        let mut writer = MemBufferWriter::new();
+        writer.add_entry("no schema");
+        let result = writer.finalize().unwrap();
+        assert!(MemBufferReader::new(&result).unwrap().schema().unwrap().is_none());
+    }
+
+    #[test]
+    fn check_schema_big_endian() {
+        let mut writer = MemBufferWriter::<BigEndian>::with_byte_order();
+        writer.set_schema(true);
+        writer.add_named_entry("values", &[1u64,2][..]).unwrap();
+        let result = writer.finalize().unwrap();
+        let reader = MemBufferReader::<BigEndian>::with_byte_order(&result).unwrap();
+        //Payloads stay aligned behind the schema block
+        assert_eq!(&reader.load_named_entry::<Cow<[u64]>>("values").unwrap()[..], &[1,2]);
+        let schema = reader.schema().unwrap().unwrap();
+        assert_eq!(schema.entry(0).unwrap().type_id, i32::from(MemBufferTypes::VectorU64));
+        assert_eq!(schema.entry(0).unwrap().element_type, Some("u64"));
+    }
+
+    #[test]
+    fn check_corrupt_schema() {
+        let mut writer = MemBufferWriter::new();
+        writer.set_checksums(false);
+        writer.set_schema(true);
+        writer.add_named_entry("a", 1u8).unwrap();
+        let result = writer.finalize().unwrap();
+        let names_start = POSITIONS_START + 12;
+        let schema_start = names_start + 8 + LittleEndian::read_u64(&result[names_start..]) as usize;
+        let schema_len = LittleEndian::read_u64(&result[schema_start..]) as usize;
+
+        //The schema block is covered by the checksum of the header
+        let mut corrupt = result.clone();
+        corrupt[schema_start + 8 + schema_len - 1] ^= 1;
+        assert!(matches!(MemBufferReader::new(&corrupt), Err(MemBufferError::HeaderChecksumMismatch)));
+
+        //A schema block which ends past the buffer
+        let mut corrupt = result.clone();
+        corrupt[schema_start..schema_start+8].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(matches!(MemBufferReader::new(&corrupt), Err(MemBufferError::TruncatedHeader { header_len: usize::MAX, .. })));
+    }
+
+    #[test]
+    fn check_typed_fields() {
+        let mut writer = MemBufferWriter::new();
+        writer.put(Article::VIEWS, 10);
+        assert_eq!(writer.len(), 2);
+        assert!(writer.load_entry::<Option<&str>>(0).unwrap().is_none());
+        writer.put(Article::TITLE, "Membuffer");
+        writer.put(Article::TAGS, Some(&[1u32,2][..]));
+        writer.put(Article::VIEWS, 20);
+        let result = writer.finalize().unwrap();
+
+        let reader = MemBufferReader::new(&result).unwrap();
+        assert_eq!(reader.get(Article::TITLE).unwrap(), "Membuffer");
+        assert_eq!(reader.get(Article::VIEWS).unwrap(), 20);
+        assert!(matches!(reader.get(Field::<u32>::new(1)), Err(MemBufferError::FieldTypeError(_,_))));
+
+        let reader = reader.with_schema::<Article>().unwrap();
+        assert_eq!(reader.get(Article::TITLE).unwrap(), "Membuffer");
+        assert_eq!(reader.get(Article::TAGS).unwrap(), Some(&[1u32,2][..]));
+        assert_eq!(reader.reader().len(), 3);
+        //Fields of other schemas are loaded through the underlying reader which checks their type
+        const OTHER_VIEWS: Field<i64> = Field::new(1);
+        assert!(matches!(reader.reader().get(OTHER_VIEWS), Err(MemBufferError::FieldTypeError(_,_))));
+        assert!(matches!(reader.reader().get_or_default(OTHER_VIEWS), Err(MemBufferError::FieldTypeError(_,_))));
+        assert_eq!(Article::TAGS.info(), FieldInfo { index: 2, type_id: MemBufferTypes::VectorU32.into(), nullable: true, optional: true });
+
+        //Nullable fields accept null entries, other fields do not
+        let mut writer = MemBufferWriter::new();
+        writer.put(Article::TAGS, None);
+        writer.put(Article::VIEWS, 1);
+        let result = writer.finalize().unwrap();
+        let reader = MemBufferReader::new(&result).unwrap();
+        assert!(matches!(reader.check_schema::<Article>(), Err(MemBufferError::FieldTypeError(x,_)) if x == MemBufferTypes::Null as i32));
+
+        let mut writer = MemBufferWriter::new();
+        writer.put(Article::TITLE, "Membuffer");
+        writer.put(Article::VIEWS, 1);
+        let result = writer.finalize().unwrap();
+        let reader = MemBufferReader::new(&result).unwrap();
+        //Option fields are optional and may be missing at the end of the buffer
+        let reader = reader.with_schema::<Article>().unwrap();
+        assert_eq!(reader.get(Article::TAGS).unwrap(), None);
+        let mut writer = MemBufferWriter::new();
+        writer.put(Article::TITLE, "Membuffer");
+        let result = writer.finalize().unwrap();
+        let reader = MemBufferReader::new(&result).unwrap();
+        assert!(matches!(reader.with_schema::<Article>(), Err(MemBufferError::KeyOutOfRange { key: 1, len: 1 })));
+
+        let mut writer = MemBufferWriter::new();
+        writer.add_entry("Membuffer");
+        writer.add_entry(1u32);
+        writer.add_entry(None::<&[u32]>);
+        let result = writer.finalize().unwrap();
+        let reader = MemBufferReader::new(&result).unwrap();
+        assert!(matches!(reader.check_schema::<Article>(), Err(MemBufferError::FieldTypeError(_,_))));
     }
 
-    ///Loads a nested MembufferWriter as reader
-    pub fn load_recursive_reader(&self, key: usize) -> Result<MemBufferReader<'a>,MemBufferError> {
-        self.intern_load_entry(key.into(), MemBufferWriter::get_mem_buffer_type())
+    #[test]
+    fn check_schema_evolution() {
+        //Version 1 stores a title and the views, version 2 retires the views and adds tags and a
+        //score
+        const TITLE: Field<&str> = Field::new(0);
+        const VIEWS: Field<u64> = Field::new(1);
+        const VIEWS_V2: Field<Option<u64>> = Field::new(1);
+        const TAGS: Field<Option<&[u32]>,V2> = Field::new(2);
+        const SCORE: Field<f64,V2> = Field::new(3);
+
+        let mut old = MemBufferWriter::new();
+        old.put(TITLE, "Membuffer");
+        old.put(VIEWS, 10);
+        let old = old.finalize().unwrap();
+
+        let mut new = MemBufferWriter::new();
+        new.put(TITLE, "Membuffer");
+        new.add_tombstone();
+        new.put(TAGS, Some(&[1u32][..]));
+        new.put(SCORE, 0.5);
+        let new = new.finalize().unwrap();
+
+        //New readers read old buffers
+        let reader = MemBufferReader::new(&old).unwrap();
+        assert_eq!(reader.get(TAGS).unwrap(), None);
+        assert_eq!(reader.get_or_default(SCORE).unwrap(), 0.0);
+        assert!(matches!(reader.get(SCORE), Err(MemBufferError::KeyOutOfRange { key: 3, len: 2 })));
+        assert_eq!(reader.load_entry_or_default::<&str>(7).unwrap(), "");
+
+        //Old readers read new buffers
+        let reader = MemBufferReader::new(&new).unwrap();
+        assert!(reader.is_retired(1).unwrap());
+        assert!(!reader.is_null(1).unwrap());
+        assert!(matches!(reader.get(VIEWS), Err(MemBufferError::RetiredEntry { key: 1 })));
+        assert_eq!(reader.get(VIEWS_V2).unwrap(), None);
+        assert_eq!(reader.get_or_default(VIEWS).unwrap(), 0);
+        assert_eq!(reader.get(TITLE).unwrap(), "Membuffer");
+
+        struct V2;
+        impl Schema for V2 {
+            fn fields() -> Vec<FieldInfo> {
+                vec![TITLE.info(), FieldInfo::retired(1), TAGS.info(), SCORE.info().optional()]
+            }
+        }
+        let reader = MemBufferReader::new(&old).unwrap().with_schema::<V2>().unwrap();
+        assert_eq!(reader.get(TAGS).unwrap(), None);
+        assert_eq!(reader.get_or_default(SCORE).unwrap(), 0.0);
+        let reader = MemBufferReader::new(&new).unwrap().with_schema::<V2>().unwrap();
+        assert_eq!(reader.get(SCORE).unwrap(), 0.5);
+        assert_eq!(reader.get(TAGS).unwrap(), Some(&[1u32][..]));
+
+        let mut writer = MemBufferWriter::from(&new).unwrap();
+        writer.retire_entry(0).unwrap();
+        assert!(matches!(writer.load_entry::<&str>(0), Err(MemBufferError::RetiredEntry { key: 0 })));
+        assert!(matches!(writer.retire_entry(4), Err(MemBufferError::KeyOutOfRange { key: 4, len: 4 })));
     }
 
+    #[test]
+    fn check_schema_compatibility() {
+        let title = Field::<&str>::new(0).info();
+        let views = Field::<u64>::new(1).info();
+        let tags = Field::<Option<&[u32]>>::new(2).info();
+        assert_eq!(check_compatibility(&[title,views], &[title,views,tags]), Ok(()));
+        assert_eq!(check_compatibility(&[title,views], &[title,FieldInfo::retired(1)]), Ok(()));
+        assert_eq!(check_compatibility(&[title,FieldInfo::retired(1)], &[title,FieldInfo::retired(1),tags]), Ok(()));
+
+        assert_eq!(check_compatibility(&[title,views], &[title]), Err(vec![BreakingChange::FieldRemoved { index: 1 }]));
+        assert_eq!(check_compatibility(&[title,FieldInfo::retired(1)], &[title,Field::<u8>::new(1).info().optional()]),
+            Err(vec![BreakingChange::SlotReused { index: 1 }]));
+        assert_eq!(check_compatibility(&[title], &[title,views]), Err(vec![BreakingChange::RequiredFieldAdded { index: 1 }]));
+        assert_eq!(check_compatibility(&[title], &[title,views.optional()]), Ok(()));
+        assert_eq!(check_compatibility(&[title,tags], &[title,Field::<Option<&[u32]>>::new(2).info().optional()]), Ok(()));
+        assert_eq!(check_compatibility(&[title,views.optional()], &[title,views]), Err(vec![BreakingChange::FieldBecameRequired { index: 1 }]));
+        assert_eq!(check_compatibility(&[title,views], &[title,Field::<Option<u64>>::new(1).info()]), Err(vec![BreakingChange::NullableChanged { index: 1 }]));
+        assert_eq!(check_compatibility(&[title,views], &[title,views,Field::<&str>::new(1).info()]), Err(vec![
+            BreakingChange::DuplicateIndex { index: 1 },
+            BreakingChange::TypeChanged { index: 1, old: MemBufferTypes::UInteger64.into(), new: MemBufferTypes::Text.into() },
+        ]));
+    }
 
-    ///Creates a new memory format reader from the given memory slice, as the readed values are
-    ///borrowed from the memory slice the reader cannot outlive the memory it borrows from
-    pub fn new(val: &'a [u8]) -> Result<MemBufferReader<'a>,MemBufferError> {
-        if val.len() < 8 {
-            return Err(MemBufferError::WrongFormat);
+    #[test]
+    fn check_buffer_array() {
+        let mut children = Vec::new();
+        for x in 0..5u64 {
+            let mut child = MemBufferWriter::<BigEndian>::with_byte_order();
+            child.add_entry("odd".repeat(x as usize).as_str());
+            child.add_entry::<&[u64]>(&[x,x+1]);
+            children.push(child);
         }
+        let mut writer = MemBufferWriter::<BigEndian>::with_byte_order();
+        writer.add_entry("odd");
+        writer.add_buffer_array(&children);
+        writer.add_buffer_array(Vec::<MemBufferWriter<BigEndian>>::new());
+        let result = writer.finalize().unwrap();
+
+        let reader = MemBufferReader::<BigEndian>::with_byte_order(&result).unwrap();
+        let array = reader.load_buffer_array(1).unwrap();
+        assert_eq!(array.len(), 5);
+        let child = array.get(3).unwrap();
+        assert_eq!(child.load_entry::<&str>(0).unwrap(), "oddoddodd");
+        assert_eq!(&child.load_entry::<Cow<[u64]>>(1).unwrap()[..], &[3,4]);
+        for (x,child) in array.iter().enumerate() {
+            assert_eq!(child.unwrap().load_entry::<&str>(0).unwrap().len(), 3*x);
+        }
+        assert!(matches!(array.get(5), Err(MemBufferError::KeyOutOfRange { key: 5, len: 5 })));
+        assert!(reader.load_buffer_array(2).unwrap().is_empty());
+        assert!(reader.load_buffer_array(0).is_err());
+        assert!(reader.load_recursive_reader(1).is_err());
+    }
 
-        let vec_len = MemBufferReader::deserialize_i32_from(val) as usize;
-        let checksum = MemBufferReader::deserialize_i32_from(&val[4..]) as usize;
-        let start = vec_len*std::mem::size_of::<InternPosition>()+8;
-        if val.len() < start || std::num::Wrapping(checksum)+std::num::Wrapping(0x7AFECAFE) != std::num::Wrapping(vec_len) {
-            return Err(MemBufferError::WrongFormat);
+    #[test]
+    fn check_buffer_array_alignment() {
+        let mut children = Vec::new();
+        for x in 0..3u64 {
+            let mut child = MemBufferWriter::new();
+            child.add_entry("odd".repeat(x as usize).as_str());
+            child.add_entry::<&[u128]>(&[x as u128]);
+            children.push(child);
         }
+        let mut writer = MemBufferWriter::new();
+        writer.add_entry("odd");
+        writer.add_buffer_array(children);
+        let result = writer.finalize().unwrap();
 
-        unsafe {
-        Ok(MemBufferReader {
-            offsets: std::slice::from_raw_parts(val[8..].as_ptr().cast::<InternPosition>(),vec_len),
-            data: &val[start..]
-        })
+        let reader = MemBufferReader::new(&result).unwrap();
+        for (x,child) in reader.load_buffer_array(1).unwrap().iter().enumerate() {
+            let values: &[u128] = child.unwrap().load_entry(1).unwrap();
+            assert_eq!(values, &[x as u128]);
         }
     }
-}
 
-impl<'a> std::fmt::Debug for MemBufferReader<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f,"Found memory buffer with payload size {}",self.data.len())
+    #[derive(Clone,Copy,Debug,PartialEq)]
+    #[repr(C)]
+    struct Record {
+        timestamp: u64,
+        low: f32,
+        high: f32,
     }
-}
-
-
-///The Writer class which sets up the schema and writes it into the memory when finished building
-pub struct MemBufferWriter {
-    types: Vec<i32>,
-    data: Vec<Vec<u8>>
-}
-
-pub trait MemBufferSerialize {
-    fn to_mem_buffer<'a>(&'a self) -> std::borrow::Cow<'a,[u8]>;
-    fn get_mem_buffer_type() -> i32; 
-}
 
-impl MemBufferSerialize for &str {
-    fn to_mem_buffer<'a>(&'a self) -> std::borrow::Cow<'a,[u8]> {
-        std::borrow::Cow::Borrowed(self.as_bytes())
+    unsafe impl MemBufferPod for Record {
+        const POD_NAME: &'static str = "Record";
     }
 
-    fn get_mem_buffer_type() -> i32 {
-        MemBufferTypes::Text.into()
+    #[derive(Clone,Copy,Debug,PartialEq)]
+    #[repr(C)]
+    struct OtherRecord {
+        id: u64,
+        count: u64,
     }
-}
 
-impl MemBufferSerialize for &String {
-    fn to_mem_buffer<'a>(&'a self) -> Cow<'a,[u8]> {
-        Cow::Borrowed(self.as_bytes())
+    unsafe impl MemBufferPod for OtherRecord {
+        const POD_NAME: &'static str = "OtherRecord";
     }
 
-    fn get_mem_buffer_type() -> i32 {
-        MemBufferTypes::Text.into()
+    #[derive(Clone,Copy,Debug,PartialEq)]
+    struct Marker;
+
+    unsafe impl MemBufferPod for Marker {
+        const POD_NAME: &'static str = "Marker";
     }
-}
 
-impl MemBufferSerialize for i32 {
-    fn to_mem_buffer<'a>(&'a self) -> Cow<'a, [u8]> {
-        Cow::Owned(unsafe{std::mem::transmute::<i32,[u8;4]>(*self)}.to_vec())
+    #[test]
+    fn check_zero_sized_pod() {
+        let mut writer = MemBufferWriter::new();
+        writer.add_entry(&[Marker,Marker][..]);
+        writer.add_entry(&Marker);
+        let result = writer.finalize().unwrap();
+
+        let reader = MemBufferReader::new(&result).unwrap();
+        assert!(matches!(reader.load_entry::<&[Marker]>(0), Err(MemBufferError::InvalidLength { len: 0, element_size: 0 })));
+        assert!(matches!(reader.load_entry::<&Marker>(1), Err(MemBufferError::InvalidLength { len: 0, element_size: 0 })));
     }
 
-    fn get_mem_buffer_type() -> i32 {
-        MemBufferTypes::Integer32.into()
+    #[test]
+    fn check_pod_records() {
+        let records = [Record { timestamp: 1, low: 0.5, high: 1.5 }, Record { timestamp: 2, low: -0.5, high: 2.5 }];
+        let mut writer = MemBufferWriter::<BigEndian>::with_byte_order();
+        writer.add_entry("odd");
+        writer.add_entry(&records[..]);
+        writer.add_entry(&records[1]);
+        let result = writer.finalize().unwrap();
+
+        let reader = MemBufferReader::<BigEndian>::with_byte_order(&result).unwrap();
+        let loaded: &[Record] = reader.load_entry(1).unwrap();
+        assert_eq!(loaded, &records);
+        assert_eq!(loaded.as_ptr() as usize % std::mem::align_of::<Record>(), 0);
+        assert_eq!(reader.load_entry::<&Record>(2).unwrap(), &records[1]);
+        assert!(matches!(reader.load_entry::<&Record>(1), Err(MemBufferError::InvalidLength { len: 32, element_size: 16 })));
+        assert!(matches!(reader.load_entry::<&[OtherRecord]>(1), Err(MemBufferError::FieldTypeError(_,_))));
+        assert!(reader.load_entry::<&[u64]>(1).is_err());
+        assert!(<&[Record]>::get_mem_buffer_type() < 0);
+        assert_ne!(<&[Record]>::get_mem_buffer_type(), <&[OtherRecord]>::get_mem_buffer_type());
     }
-}
 
+    #[test]
+    fn check_slice_alignment() {
+        let mut writer = MemBufferWriter::new();
+        writer.add_entry("odd");
+        writer.add_entry::<&[u64]>(&[0,1,2,3,4,5]);
+        writer.add_entry("x");
+        writer.add_entry::<&[u32]>(&[6,7]);
+        let result = writer.finalize().unwrap();
 
-impl MemBufferSerialize for u64 {
-    fn to_mem_buffer<'a>(&'a self) -> Cow<'a, [u8]> {
-        Cow::Owned(unsafe{std::mem::transmute::<u64,[u8;8]>(*self)}.to_vec())
+        let reader = MemBufferReader::new(&result).unwrap();
+        assert_eq!((result.len() - reader.payload_len() + reader.position(1).pos.start as usize) % 8, 0);
+        assert_eq!((result.len() - reader.payload_len() + reader.position(3).pos.start as usize) % 4, 0);
+
+        //The alignment is kept when reloading the writer
+        let reloaded = MemBufferWriter::from(&result).unwrap().finalize().unwrap();
+        assert_eq!(reloaded, result);
+
+        //Shift the whole buffer by one byte, the slices cannot be borrowed anymore
+        let mut shifted = vec![0u8; result.len()+1];
+        shifted[1..].copy_from_slice(&result);
+        let reader = MemBufferReader::new(&shifted[1..]).unwrap();
+        assert!(matches!(reader.load_entry::<&[u64]>(1), Err(MemBufferError::Misaligned { alignment: 8 })));
+        let copied = reader.load_entry::<Cow<[u64]>>(1).unwrap();
+        assert!(matches!(copied, Cow::Owned(_)));
+        assert_eq!(&copied[..], &[0,1,2,3,4,5]);
+        assert_eq!(&reader.load_entry::<Cow<[u32]>>(3).unwrap()[..], &[6,7]);
     }
 
-    fn get_mem_buffer_type() -> i32 {
-        1021
-    }
-}
+    #[test]
+    fn check_slice_invalid_length() {
+        let mut writer = MemBufferWriter::new();
+        writer.set_checksums(false);
+        writer.add_entry(&[1u8,2,3,4,5,6,7][..]);
+        let mut result = writer.finalize().unwrap();
+        //Pretend the byte entry is a u64 slice
+        result[POSITIONS_START+8..POSITIONS_START+12].copy_from_slice(&(MemBufferTypes::VectorU64 as i32).to_le_bytes());
+        reseal_header(&mut result, 1);
 
-impl MemBufferSerialize for &[u8] {
-    fn to_mem_buffer<'a>(&'a self) -> Cow<'a, [u8]> {
-        Cow::Borrowed(self)
+        let reader = MemBufferReader::new(&result).unwrap();
+        assert!(matches!(reader.load_entry::<&[u64]>(0), Err(MemBufferError::InvalidLength { len: 7, element_size: 8 })));
+        assert!(reader.load_entry::<Cow<[u64]>>(0).is_err());
     }
 
-    fn get_mem_buffer_type() -> i32 {
-        MemBufferTypes::VectorU8.into()
+    #[test]
+    fn check_big_endian() {
+        let mut nested = MemBufferWriter::<BigEndian>::with_byte_order();
+        nested.add_entry(42);
+
+        let mut writer = MemBufferWriter::<BigEndian>::with_byte_order();
+        writer.add_entry(-7);
+        writer.add_entry(0x0102030405060708u64);
+        writer.add_entry::<&[u64]>(&[1,2,0x0102030405060708]);
+        writer.add_entry::<&[u32]>(&[3,4]);
+        writer.add_entry("big");
+        writer.add_entry(nested);
+        let result = writer.finalize().unwrap();
+
+        //The entry count is stored in big endian
+        assert_eq!(BigEndian::read_i32(&result[12..]), 6);
+        assert!(matches!(MemBufferReader::new(&result), Err(MemBufferError::WrongByteOrder { big_endian: true })));
+
+        let reader = MemBufferReader::<BigEndian>::with_byte_order(&result).unwrap();
+        assert_eq!(reader.load_entry::<i32>(0).unwrap(), -7);
+        assert_eq!(reader.load_entry::<u64>(1).unwrap(), 0x0102030405060708);
+        assert!(matches!(reader.load_entry::<&[u64]>(2), Err(MemBufferError::WrongByteOrder { big_endian: true })));
+        assert_eq!(&reader.load_entry::<Cow<[u64]>>(2).unwrap()[..], &[1,2,0x0102030405060708]);
+        assert_eq!(&reader.load_entry::<Cow<[u32]>>(3).unwrap()[..], &[3,4]);
+        assert_eq!(reader.load_entry::<&str>(4).unwrap(), "big");
+        assert_eq!(reader.load_recursive_reader(5).unwrap().load_entry::<i32>(0).unwrap(), 42);
+
+        let reloaded = MemBufferWriter::<BigEndian>::from_with_byte_order(&result).unwrap();
+        assert_eq!(reloaded.finalize().unwrap(), result);
     }
-}
 
-impl MemBufferSerialize for &[u64] {
-    fn to_mem_buffer<'a>(&'a self) -> Cow<'a,[u8]> {
-        let val: *const u64 = self.as_ptr();
-        let cast_memory = val.cast::<u8>();
-        let mem_length = self.len() * std::mem::size_of::<u64>();
-        Cow::Borrowed(unsafe{ std::slice::from_raw_parts(cast_memory, mem_length)})
-    }
+    #[test]
+    fn check_nested_byte_order() {
+        let mut nested = MemBufferWriter::<BigEndian>::with_byte_order();
+        nested.add_entry(42);
 
-    fn get_mem_buffer_type() -> i32 {
-        MemBufferTypes::VectorU64.into()
-    }
-}
+        let mut writer = MemBufferWriter::new();
+        writer.add_entry(nested);
+        assert!(matches!(writer.finalize(), Err(MemBufferError::WrongByteOrder { big_endian: true })));
 
-impl MemBufferSerialize for &[u32] {
-    fn to_mem_buffer<'a>(&'a self) -> Cow<'a,[u8]> {
-        let val: *const u32 = self.as_ptr();
-        let cast_memory = val.cast::<u8>();
-        let mem_length = self.len() * std::mem::size_of::<u32>();
-        Cow::Borrowed(unsafe{ std::slice::from_raw_parts(cast_memory, mem_length)})
+        let mut nested = MemBufferWriter::<BigEndian>::with_byte_order();
+        nested.add_entry(42);
+        let mut writer = MemBufferWriter::new();
+        writer.add_entry(1);
+        assert!(matches!(writer.set_entry(Some(nested), 0), Err(MemBufferError::WrongByteOrder { big_endian: true })));
+        assert!(matches!(writer.add_map(vec![("one",MemBufferWriter::<BigEndian>::with_byte_order())]), Err(MemBufferError::WrongByteOrder { big_endian: true })));
+        assert_eq!(writer.len(), 1);
+        let result = writer.finalize().unwrap();
+        assert_eq!(MemBufferReader::new(&result).unwrap().load_entry::<i32>(0).unwrap(), 1);
+
+        //The error of a nested writer is reported by every writer it is added to
+        let mut inner = MemBufferWriter::<BigEndian>::with_byte_order();
+        inner.add_entry(MemBufferWriter::new());
+        let mut nested = MemBufferWriter::<BigEndian>::with_byte_order();
+        nested.add_entry(inner);
+        let mut writer = MemBufferWriter::<BigEndian>::with_byte_order();
+        writer.add_entry(nested);
+        assert!(matches!(writer.finalize(), Err(MemBufferError::WrongByteOrder { big_endian: false })));
     }
 
-    fn get_mem_buffer_type() -> i32 {
-        MemBufferTypes::VectorU32.into()
+    #[test]
+    fn check_legacy_i32_helpers() {
+        let mut raw = Vec::new();
+        MemBufferWriter::serialize_i32_to(0x01020304, &mut raw);
+        assert_eq!(raw, 0x01020304i32.to_ne_bytes());
+        assert_eq!(MemBufferReader::deserialize_i32_from(&raw), 0x01020304);
     }
-}
 
+    #[test]
+    fn check_little_endian_is_default() {
+        let mut writer = MemBufferWriter::new();
+        writer.add_entry(0x01020304);
+        let result = writer.finalize().unwrap();
 
-impl MemBufferSerialize for MemBufferWriter {
-    fn to_mem_buffer<'a>(&'a self) -> Cow<'a,[u8]> {
-        let ret = self.finalize();
-        Cow::Owned(ret)
+        assert_eq!(&result[0..4], b"MBUF");
+        assert_eq!(LittleEndian::read_i32(&result[12..]), 1);
+        assert!(MemBufferReader::<BigEndian>::with_byte_order(&result).is_err());
+        assert_eq!(MemBufferReader::<LittleEndian>::with_byte_order(&result).unwrap().load_entry::<i32>(0).unwrap(), 0x01020304);
     }
 
-    fn get_mem_buffer_type() -> i32 {
-        MemBufferTypes::MemBuffer.into()
+    #[test]
+    fn check_legacy_native_format() {
+        //Layout written by membuffer 0.3: count, count - 0x7AFECAFE and start, end, type of
+        //every entry in the native byte order followed by the payload
+        let mut legacy = Vec::new();
+        for val in [1i32, 1i32.wrapping_sub(0x7AFECAFE), 0, 5, MemBufferTypes::Text as i32].iter() {
+            legacy.extend_from_slice(&val.to_ne_bytes());
+        }
+        legacy.extend_from_slice(b"Earth");
+
+        let reader = MemBufferReader::<NativeEndian>::with_byte_order(&legacy).unwrap();
+        assert_eq!(reader.version(), LEGACY_FORMAT_VERSION);
+        assert_eq!(reader.len(), 1);
+        assert_eq!(reader.load_entry::<&str>(0).unwrap(), "Earth");
     }
-}
 
-impl MemBufferWriter {
-    ///Creates a new empty memory format writer
-    pub fn new() -> MemBufferWriter {
-        MemBufferWriter {
-            types: Vec::new(),
-            data: Vec::new()
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn check_legacy_serde_entries() {
+        //Serde entries were stored as plain byte vectors just like raw bytes
+        let mut legacy = Vec::new();
+        for val in [2i32, 2i32.wrapping_sub(0x7AFECAFE), 0, 4, MemBufferTypes::VectorU8 as i32, 4, 7, MemBufferTypes::VectorU8 as i32].iter() {
+            legacy.extend_from_slice(&val.to_ne_bytes());
         }
+        legacy.extend_from_slice(&100i32.to_le_bytes());
+        legacy.extend_from_slice(&[7,8,9]);
+        let reader = MemBufferReader::<NativeEndian>::with_byte_order(&legacy).unwrap();
+        assert_eq!(reader.load_serde_entry::<i32>(0).unwrap(), 100);
+        assert_eq!(reader.load_entry::<&[u8]>(1).unwrap(), &[7,8,9]);
+
+        //Migrating the buffer keeps the types, bincode entries have to be marked explicitly
+        let mut writer = MemBufferWriter::<NativeEndian>::from_with_byte_order(&legacy).unwrap();
+        let migrated = writer.finalize().unwrap();
+        let reader = MemBufferReader::<NativeEndian>::with_byte_order(&migrated).unwrap();
+        assert_eq!(reader.version(), FORMAT_VERSION);
+        assert_eq!(reader.load_entry::<&[u8]>(1).unwrap(), &[7,8,9]);
+        assert!(matches!(reader.load_serde_entry::<i32>(0), Err(MemBufferError::FieldTypeError(_,_))));
+
+        writer.mark_serde_entry(0).unwrap();
+        assert!(matches!(writer.mark_serde_entry(0), Err(MemBufferError::FieldTypeError(_,_))));
+        assert!(matches!(writer.mark_serde_entry(2), Err(MemBufferError::KeyOutOfRange { key: 2, len: 2 })));
+        let migrated = writer.finalize().unwrap();
+        let reader = MemBufferReader::<NativeEndian>::with_byte_order(&migrated).unwrap();
+        assert_eq!(reader.load_serde_entry::<i32>(0).unwrap(), 100);
+        assert_eq!(reader.load_entry::<&[u8]>(1).unwrap(), &[7,8,9]);
     }
 
-    ///Create a new Membuffer writer from the given memory, this will enable the writer to add
-    ///more data to the previous version, to do so the writer does a full reload of the memory
-    ///therefore it is an expensive operation if the structure adding fields to is huge.
-    ///```rust
-    ///use membuffer::{MemBufferWriter,MemBufferReader};
-    ///
-    ///let mut value = MemBufferWriter::new();
-    ///value.add_entry("Hello");
-    ///value.add_entry("World");
-    ///
-    ///let data = value.finalize();
-    ///
-    /////Save data to disk or anything like that
-    /////Then load it again and add more data by doing this
-    ///
-    ///let mut writer_adder = MemBufferWriter::from(&data).unwrap();
-    /////The writer creates a new Vector to hold the data therefore no mutable reference to data is
-    /////stored
-    ///writer_adder.add_entry("Damn I forgot");
-    ///
-    ///let new_data = writer_adder.finalize();
-    /////new_data will now contain an entry for "Hello" an entry for "World" and an entry
-    /////for "Damn I forgot" 
-    ///
-    ///```
-    pub fn from<'a>(raw_memory: &'a [u8]) -> Result<MemBufferWriter,MemBufferError> {
-        let reader = MemBufferReader::new(raw_memory)?;
-        let mut types : Vec<i32> = Vec::new();
-        let mut data : Vec<Vec<u8>> = Vec::new();
-        for x in reader.offsets.iter() {
-            types.push(x.variable_type);
-            data.push(reader.data[x.pos.start as usize..x.pos.end as usize].to_vec())
-        }
+    #[test]
+    fn check_versioned_header() {
+        let mut writer = MemBufferWriter::new();
+        writer.add_entry("Earth");
+        let result = writer.finalize().unwrap();
 
-        Ok(MemBufferWriter {
-            types,
-            data
-        })
-    }
+        let reader = MemBufferReader::new(&result).unwrap();
+        assert_eq!(reader.version(), FORMAT_VERSION);
+        assert_eq!(reader.flags(), FLAG_ALIGNED | FLAG_CHECKSUMS);
 
-    ///Serializes the integer to the memory slice
-    pub fn serialize_i32_to(val: i32, to: &mut Vec<u8>) {
-        to.write_i32::<NativeEndian>(val).unwrap();
-    }
+        let mut newer = result.clone();
+        newer[4..6].copy_from_slice(&(FORMAT_VERSION+1).to_le_bytes());
+        assert!(matches!(MemBufferReader::new(&newer), Err(MemBufferError::UnsupportedVersion { version }) if version == FORMAT_VERSION+1));
 
-    ///Adds an entry to the writer the only requirement is the serializable trait
-    pub fn add_entry<T: MemBufferSerialize>(&mut self, val: T) {
-        let slice = val.to_mem_buffer();
-        self.types.push(T::get_mem_buffer_type());
-        self.data.push(slice.to_vec());
-    }
+        let mut compressed = result.clone();
+        compressed[8..12].copy_from_slice(&(FLAG_ALIGNED | FLAG_COMPRESSED).to_le_bytes());
+        assert!(matches!(MemBufferReader::new(&compressed), Err(MemBufferError::UnsupportedFlags { flags: FLAG_COMPRESSED })));
 
-    pub fn set_entry<T: MemBufferSerialize>(&mut self, val: T, index: usize) {
-        self.data[index] = val.to_mem_buffer().to_vec();
-        self.types[index] = T::get_mem_buffer_type();
+        let mut big_endian = result;
+        big_endian[8..12].copy_from_slice(&(FLAG_ALIGNED | FLAG_BIG_ENDIAN).to_le_bytes());
+        assert!(matches!(MemBufferReader::new(&big_endian), Err(MemBufferError::WrongByteOrder { big_endian: true })));
     }
 
-    pub fn load_entry<'a, T: MemBufferDeserialize<'a,T>+MemBufferSerialize>(&'a self, index: usize) -> Result<T,MemBufferError> {
-        if T::get_mem_buffer_type() != self.types[index] {
-            return Err(MemBufferError::FieldTypeError(self.types[index],T::get_mem_buffer_type()));
-        }
-        return T::from_mem_buffer(&self.data[index]);
+    #[test]
+    fn check_checksums() {
+        let mut writer = MemBufferWriter::new();
+        writer.add_entry("Der moderne Prometheus");
+        writer.add_entry::<&[u64]>(&[1,2,3]);
+        let result = writer.finalize().unwrap();
+        assert!(MemBufferReader::new(&result).unwrap().verify_checksums().is_ok());
+
+        //Bit rot in the payload is detected by the entry checksums when the entry is loaded
+        let mut corrupted = result.clone();
+        let pos = corrupted.windows(7).position(|x| x == b"moderne").unwrap();
+        corrupted[pos] ^= 0x20;
+        let reader = MemBufferReader::new(&corrupted).unwrap();
+        assert!(matches!(reader.load_entry::<&str>(0), Err(MemBufferError::ChecksumMismatch { key: 0 })));
+        assert!(matches!(reader.load_entry_or_default::<&str>(0), Err(MemBufferError::ChecksumMismatch { key: 0 })));
+        assert!(matches!(reader.verify_checksums(), Err(MemBufferError::ChecksumMismatch { key: 0 })));
+        assert_eq!(reader.load_entry::<&[u64]>(1).unwrap(), &[1,2,3]);
+
+        let reader = reader.without_checksums();
+        assert_eq!(reader.load_entry::<&str>(0).unwrap(), "Der Moderne Prometheus");
+        assert!(matches!(reader.verify_checksums(), Err(MemBufferError::ChecksumMismatch { key: 0 })));
+        let reader = reader.with_lazy_checksums();
+        assert!(matches!(reader.load_entry::<&str>(0), Err(MemBufferError::ChecksumMismatch { key: 0 })));
+
+        //Bit rot in the header is always detected
+        let mut corrupted = result;
+        corrupted[POSITIONS_START+1] ^= 1;
+        assert!(matches!(MemBufferReader::new(&corrupted), Err(MemBufferError::HeaderChecksumMismatch)));
     }
 
-    pub fn len(&self) -> usize {
-        self.types.len()
-    }
+    #[test]
+    fn check_without_checksums() {
+        let mut writer = MemBufferWriter::new();
+        writer.set_checksums(false);
+        writer.add_entry("Earth");
+        let mut result = writer.finalize().unwrap();
+        let last = result.len()-1;
+        result[last] = b'a';
 
-    ///Adds a serde serializable entry into the structure as serializer serde_json is used.
-    ///Internally it is saved as a string.
-    pub fn add_serde_entry<T: Serialize>(&mut self,val: &T) {
-        let as_str = bincode::serialize(val).unwrap();
-        self.add_entry(&as_str[..]);
+        let reader = MemBufferReader::new(&result).unwrap();
+        assert_eq!(reader.flags() & FLAG_CHECKSUMS, 0);
+        assert!(reader.verify_checksums().is_ok());
+        assert_eq!(reader.load_entry::<&str>(0).unwrap(), "Earta");
     }
 
+    #[test]
+    fn check_offsets_64() {
+        let mut writer = MemBufferWriter::<BigEndian>::with_byte_order();
+        writer.set_offset_width(OffsetWidth::Bits64);
+        writer.add_entry("Earth");
+        writer.add_entry::<&[u64]>(&[1,2,3]);
+        let result = writer.finalize().unwrap();
 
-    ///Finalize the schema and return the memory slice holding the whole vector
-    pub fn finalize(&self) -> Vec<u8> {
-        let mut var: Vec<u8> = Vec::with_capacity(10_000_000);
-        MemBufferWriter::serialize_i32_to(self.types.len() as i32,&mut var);
-        MemBufferWriter::serialize_i32_to((std::num::Wrapping(self.types.len() as i32)-std::num::Wrapping(0x7AFECAFE as i32)).0,&mut var);
-        let mut offset = 0;
-        for val in 0..self.types.len() {
-            MemBufferWriter::serialize_i32_to(offset as i32, &mut var);
-            MemBufferWriter::serialize_i32_to(self.data[val].len() as i32+offset as i32, &mut var);
-            MemBufferWriter::serialize_i32_to(self.types[val], &mut var);
-            offset+=self.data[val].len();
-        }
-        for x in self.data.iter() {
-            var.extend_from_slice(x);
-        }
-        var
-    }
-}
+        let reader = MemBufferReader::<BigEndian>::with_byte_order(&result).unwrap();
+        assert_eq!(reader.flags() & FLAG_OFFSETS_64, FLAG_OFFSETS_64);
+        reader.verify_checksums().unwrap();
+        assert_eq!(reader.load_entry::<&str>(0).unwrap(), "Earth");
+        assert_eq!(&reader.load_entry::<Cow<[u64]>>(1).unwrap()[..], &[1,2,3]);
 
+        let reloaded = MemBufferWriter::<BigEndian>::from_with_byte_order(&result).unwrap();
+        let result = reloaded.finalize().unwrap();
+        let reader = MemBufferReader::<BigEndian>::with_byte_order(&result).unwrap();
+        assert_eq!(reader.flags() & FLAG_OFFSETS_64, 0);
+        assert_eq!(reader.load_entry::<&str>(0).unwrap(), "Earth");
+    }
 
+    ///Lowered limit of 32 bit offsets which makes overflowing it cheap
+    const TEST_MAX_OFFSET_32: usize = 1 << 16;
 
-#[cfg(test)]
-mod tests {
-    use super::{MemBufferWriter,MemBufferReader,MemBufferError,MemBufferTypes,MemBufferSerialize};
-    use serde::{Serialize,Deserialize};
+    #[test]
+    fn check_offsets_auto_promote() {
+        let huge = vec![7u8; TEST_MAX_OFFSET_32];
+        let mut writer = MemBufferWriter::new();
+        writer.max_offset_32 = TEST_MAX_OFFSET_32;
+        writer.add_entry("Earth");
+        let small = writer.finalize().unwrap();
+        assert_eq!(MemBufferReader::new(&small).unwrap().flags() & FLAG_OFFSETS_64, 0);
 
-    #[derive(Serialize,Deserialize)]
-    struct HeavyStruct {
-        vec: Vec<u64>,
-        name: String,
-        frequency: i32,
-        id: i32,
+        writer.add_entry(&huge[..]);
+        writer.add_entry(100);
+        let result = writer.finalize().unwrap();
+        let reader = MemBufferReader::new(&result).unwrap();
+        assert_eq!(reader.flags() & FLAG_OFFSETS_64, FLAG_OFFSETS_64);
+        assert_eq!(reader.load_entry::<&str>(0).unwrap(), "Earth");
+        assert_eq!(reader.load_entry::<&[u8]>(1).unwrap().len(), TEST_MAX_OFFSET_32);
+        assert_eq!(reader.load_entry::<i32>(2).unwrap(), 100);
     }
 
     #[test]
-    fn check_enum_usage() {
+    fn check_offsets_32_overflow() {
+        let huge = vec![7u8; TEST_MAX_OFFSET_32];
         let mut writer = MemBufferWriter::new();
-        writer.add_entry("Der moderne Prometheus");
-        writer.add_entry("Dies hier ist nur ein Satz");
-        writer.add_entry::<&[u64]>(&vec![0,1,2,3,4,5]);
-
-        let result = writer.finalize();
+        writer.max_offset_32 = TEST_MAX_OFFSET_32;
+        writer.set_offset_width(OffsetWidth::Bits32);
+        writer.add_entry("Earth");
+        writer.add_entry(&huge[..]);
+        writer.add_entry(100);
+        assert!(matches!(writer.finalize(), Err(MemBufferError::OffsetOverflow { key: 1 })));
 
+        //Nested buffers cannot report the overflow and fall back to 64 bit offsets
+        let mut outer = MemBufferWriter::new();
+        outer.max_offset_32 = TEST_MAX_OFFSET_32;
+        outer.add_entry(writer);
+        let result = outer.finalize().unwrap();
         let reader = MemBufferReader::new(&result).unwrap();
-
-        let _: &str = reader.load_entry(0).unwrap();
-        let _: &str = reader.load_entry(1).unwrap();
-        let _: &[u64] = reader.load_entry(2).unwrap();
+        assert_eq!(reader.flags() & FLAG_OFFSETS_64, FLAG_OFFSETS_64);
+        let nested = reader.load_recursive_reader(0).unwrap();
+        assert_eq!(nested.flags() & FLAG_OFFSETS_64, FLAG_OFFSETS_64);
+        assert_eq!(nested.load_entry::<i32>(2).unwrap(), 100);
     }
 
     #[test]
@@ -493,7 +4882,7 @@ mod tests {
         let mut writer = MemBufferWriter::new();
         writer.add_entry::<&[u32]>(&vec![0,1,2,3,4,5]);
 
-        let result = writer.finalize();
+        let result = writer.finalize().unwrap();
 
         let reader = MemBufferReader::new(&result).unwrap();
 
@@ -508,7 +4897,9 @@ mod tests {
         assert_eq!(<i32 as MemBufferSerialize>::get_mem_buffer_type(),MemBufferTypes::Integer32 as i32);
         assert_eq!(<&[u8] as MemBufferSerialize>::get_mem_buffer_type(),MemBufferTypes::VectorU8 as i32);
         assert_eq!(<&[u64] as MemBufferSerialize>::get_mem_buffer_type(),MemBufferTypes::VectorU64 as i32);
-        assert_eq!(MemBufferWriter::get_mem_buffer_type(),MemBufferTypes::MemBuffer as i32);
+        assert_eq!(<MemBufferWriter>::get_mem_buffer_type(),MemBufferTypes::MemBuffer as i32);
+        //Buffers written by membuffer 0.3 stored u64 under this id
+        assert_eq!(<u64 as MemBufferSerialize>::get_mem_buffer_type(),1021);
     }
 
     #[test]
@@ -518,7 +4909,7 @@ mod tests {
         writer.add_entry("Dies hier ist nur ein Satz");
         writer.add_entry::<&[u64]>(&vec![0,1,2,3,4,5]);
 
-        let mut result = writer.finalize();
+        let mut result = writer.finalize().unwrap();
         result[0] = 100;
 
 
@@ -526,6 +4917,172 @@ mod tests {
         assert_eq!(reader.is_err(),true);
     }
 
+    #[test]
+    fn check_corrupt_positions() {
+        let mut writer = MemBufferWriter::new();
+        writer.set_checksums(false);
+        writer.add_entry("Der moderne Prometheus");
+        writer.add_entry("Dies hier ist nur ein Satz");
+        let result = writer.finalize().unwrap();
+
+        //End of the second entry points past the payload
+        let mut corrupted = result.clone();
+        corrupted[POSITIONS_START+12+4..POSITIONS_START+12+8].copy_from_slice(&1000i32.to_le_bytes());
+        assert!(matches!(MemBufferReader::new(&corrupted), Err(MemBufferError::HeaderChecksumMismatch)));
+        reseal_header(&mut corrupted, 2);
+        match MemBufferReader::new(&corrupted).unwrap_err() {
+            MemBufferError::InvalidPosition { key, end, .. } => {
+                assert_eq!(key, 1);
+                assert_eq!(end, 1000);
+            }
+            err => panic!("Unexpected error {}", err)
+        }
+
+        //Negative start of the first entry
+        let mut corrupted = result.clone();
+        corrupted[POSITIONS_START..POSITIONS_START+4].copy_from_slice(&(-5i32).to_le_bytes());
+        reseal_header(&mut corrupted, 2);
+        assert!(MemBufferReader::new(&corrupted).is_err());
+
+        //Inverted range for the first entry
+        let mut corrupted = result.clone();
+        corrupted[POSITIONS_START..POSITIONS_START+4].copy_from_slice(&20i32.to_le_bytes());
+        corrupted[POSITIONS_START+4..POSITIONS_START+8].copy_from_slice(&10i32.to_le_bytes());
+        reseal_header(&mut corrupted, 2);
+        assert!(MemBufferReader::new(&corrupted).is_err());
+
+        //The unchecked reader skips the walk over the entries
+        let reader = MemBufferReader::new_unchecked(&corrupted).unwrap();
+        assert_eq!(reader.len(), 2);
+        assert_eq!(reader.load_entry::<&str>(1).unwrap(), "Dies hier ist nur ein Satz");
+    }
+
+    #[test]
+    fn check_corrupt_entry_count() {
+        let mut result = vec![0u8; 8];
+        let count = -1i32;
+        result[0..4].copy_from_slice(&count.to_ne_bytes());
+        result[4..8].copy_from_slice(&count.wrapping_sub(0x7AFECAFE).to_ne_bytes());
+        assert!(MemBufferReader::new(&result).is_err());
+
+        let count = i32::MAX;
+        result[0..4].copy_from_slice(&count.to_ne_bytes());
+        result[4..8].copy_from_slice(&count.wrapping_sub(0x7AFECAFE).to_ne_bytes());
+        assert!(MemBufferReader::new(&result).is_err());
+    }
+
+    #[test]
+    fn check_truncated_header() {
+        let mut writer = MemBufferWriter::new();
+        writer.add_entry("Earth");
+        writer.add_entry(100);
+        let result = writer.finalize().unwrap();
+        let header_len = result.len()-MemBufferReader::new(&result).unwrap().payload_len();
+
+        for len in [4, 14, header_len-1].iter() {
+            match MemBufferReader::new(&result[..*len]) {
+                Err(MemBufferError::TruncatedHeader { len: x, header_len: y }) => {
+                    assert_eq!(x, *len);
+                    assert!(y > x && y <= header_len);
+                }
+                _ => panic!("Expected a truncated header"),
+            }
+        }
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn check_serde_decode_error() {
+        let mut writer = MemBufferWriter::new();
+        writer.add_serde_entry(&1u8).unwrap();
+        let result = writer.finalize().unwrap();
+
+        let reader = MemBufferReader::new(&result).unwrap();
+        let err = reader.load_serde_entry::<String>(0).unwrap_err();
+        assert!(matches!(err, MemBufferError::Deserialize { key: 0, .. }));
+        assert!(std::error::Error::source(&err).is_some());
+
+        //The error composes with boxed errors and ?
+        fn load(reader: &MemBufferReader) -> Result<String,Box<dyn std::error::Error + Send + Sync>> {
+            Ok(reader.load_serde_entry(0)?)
+        }
+        assert!(load(&reader).unwrap_err().to_string().starts_with("Memory buffer error"));
+    }
+
+    #[cfg(feature = "bincode")]
+    struct Unserializable;
+
+    #[cfg(feature = "bincode")]
+    impl Serialize for Unserializable {
+        fn serialize<S: serde::Serializer>(&self, _: S) -> Result<S::Ok,S::Error> {
+            Err(serde::ser::Error::custom("not serializable"))
+        }
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn check_serde_encode_error() {
+        let mut writer = MemBufferWriter::new();
+        let err = writer.add_serde_entry(&Unserializable).unwrap_err();
+        assert!(matches!(err, MemBufferError::Serialize(_)));
+        assert!(writer.is_empty());
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn check_serde_codec_type() {
+        let mut writer = MemBufferWriter::new();
+        writer.add_entry(&[1u8,2,3,4][..]);
+        writer.add_serde_entry(&100i32).unwrap();
+        let result = writer.finalize().unwrap();
+
+        let reader = MemBufferReader::new(&result).unwrap();
+        assert!(matches!(reader.load_serde_entry::<i32>(0), Err(MemBufferError::FieldTypeError(x,y)) if x == MemBufferTypes::VectorU8 as i32 && y == MemBufferTypes::SerdeBincode as i32));
+        assert_eq!(reader.load_serde_entry::<i32>(1).unwrap(), 100);
+        assert!(reader.load_entry::<&[u8]>(1).is_err());
+    }
+
+    #[cfg(all(feature = "json", feature = "bincode"))]
+    #[test]
+    fn check_serde_json() {
+        use super::{Bincode,Json};
+        let value = HeavyStruct {
+            vec: vec![100,20,1],
+            name: String::from("membuffer!"),
+            frequency: 10,
+            id: 200,
+        };
+        let mut writer = MemBufferWriter::new();
+        writer.add_serde_entry_with::<Json,_>(&value).unwrap();
+        writer.add_serde_entry_with::<Bincode,_>(&value).unwrap();
+        let result = writer.finalize().unwrap();
+
+        let reader = MemBufferReader::new(&result).unwrap();
+        let json: HeavyStruct = reader.load_serde_entry_with::<Json,_>(0).unwrap();
+        assert_eq!(json.name, "membuffer!");
+        assert!(reader.load_serde_entry::<HeavyStruct>(0).is_err());
+        assert!(reader.load_serde_entry_with::<Json,HeavyStruct>(1).is_err());
+        assert_eq!(reader.load_serde_entry::<HeavyStruct>(1).unwrap().vec, vec![100,20,1]);
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn check_serde_entry_or_default() {
+        let mut writer = MemBufferWriter::new();
+        writer.add_serde_entry(&String::from("Earth")).unwrap();
+        writer.add_serde_entry(&1u8).unwrap();
+        writer.add_entry(None::<&str>);
+        writer.add_tombstone();
+        let result = writer.finalize().unwrap();
+
+        let reader = MemBufferReader::new(&result).unwrap();
+        assert_eq!(reader.try_load_serde_entry_or_default::<String>(0).unwrap(), "Earth");
+        assert_eq!(reader.try_load_serde_entry_or_default::<String>(2).unwrap(), "");
+        assert_eq!(reader.try_load_serde_entry_or_default::<String>(3).unwrap(), "");
+        assert_eq!(reader.try_load_serde_entry_or_default::<String>(4).unwrap(), "");
+        assert!(matches!(reader.try_load_serde_entry_or_default::<String>(1), Err(MemBufferError::Deserialize { key: 1, .. })));
+    }
+
     #[test]
     fn check_read_attributes() {
         let mut writer = MemBufferWriter::new();
@@ -535,30 +5092,29 @@ mod tests {
         writer.add_entry(str1);
         writer.add_entry(str2);
         writer.add_entry(str3);
-        let result = writer.finalize();
+        let result = writer.finalize().unwrap();
 
         let reader = MemBufferReader::new(&result).unwrap();
-        let positions = &reader.offsets;
-
-        assert_eq!(positions.len(),3);
-        let zero = &positions[0];
+        assert_eq!(reader.len(),3);
+        let zero = reader.position(0);
         assert_eq!(zero.variable_type,MemBufferTypes::Text as i32);
         assert_eq!(zero.pos.start,0);
-        assert_eq!(zero.pos.end - zero.pos.start,str1.as_bytes().len() as i32);
+        assert_eq!(zero.pos.end - zero.pos.start,str1.as_bytes().len() as i64);
 
-        let one = &positions[1];
+        let one = reader.position(1);
         assert_eq!(one.variable_type,MemBufferTypes::Text as i32);
-        assert_eq!(one.pos.start,str1.as_bytes().len() as i32);
-        assert_eq!(one.pos.end - one.pos.start,str2.as_bytes().len() as i32);
+        assert_eq!(one.pos.start,str1.as_bytes().len() as i64);
+        assert_eq!(one.pos.end - one.pos.start,str2.as_bytes().len() as i64);
 
-        let two = &positions[2];
+        let two = reader.position(2);
         assert_eq!(two.variable_type,MemBufferTypes::Text as i32);
         assert_eq!(two.pos.start as usize,str1.as_bytes().len() + str2.as_bytes().len());
-        assert_eq!(two.pos.end - two.pos.start,str3.as_bytes().len() as i32);
+        assert_eq!(two.pos.end - two.pos.start,str3.as_bytes().len() as i64);
 
         assert_eq!(reader.load_entry::<&str>(2).unwrap(),str3);
     }
 
+    #[cfg(feature = "bincode")]
     #[test]
     fn check_serde_capability() {
         let value = HeavyStruct {
@@ -568,8 +5124,8 @@ mod tests {
             id: 200,
         };
         let mut writer = MemBufferWriter::new();
-        writer.add_serde_entry(&value);
-        let result = writer.finalize();
+        writer.add_serde_entry(&value).unwrap();
+        let result = writer.finalize().unwrap();
  
         let reader = MemBufferReader::new(&result).unwrap();
         let struc: HeavyStruct = reader.load_serde_entry(0).unwrap();
@@ -586,37 +5142,77 @@ mod tests {
         let string = String::from("ok nice");
         writer.add_entry("Earth");
         writer.add_entry::<&String>(&string);
-        let result = writer.finalize();
+        let result = writer.finalize().unwrap();
 
         let reader = MemBufferReader::new(&result).unwrap();
         assert_eq!(reader.load_entry::<&str>(0).unwrap(), "Earth");
         assert_eq!(reader.load_entry::<&str>(1).unwrap(), "ok nice");
     }
 
+    #[test]
+    fn check_invalid_utf8() {
+        let mut writer = MemBufferWriter::new();
+        //Without entry checksums the broken text reaches the utf-8 validation
+        writer.set_checksums(false);
+        writer.add_entry("Earth");
+        writer.add_entry(&[1u8,2,3][..]);
+        writer.add_entry("Moon");
+        let mut result = writer.finalize().unwrap();
+        assert!(MemBufferReader::new(&result).unwrap().verify_text_entries().is_ok());
+
+        //Break the first byte of the payload which belongs to "Earth"
+        let earth = result.windows(5).position(|x| x == b"Earth").unwrap();
+        result[earth] = 0xFF;
+        let reader = MemBufferReader::new(&result).unwrap();
+        assert!(matches!(reader.load_entry::<&str>(0), Err(MemBufferError::InvalidUtf8(_))));
+        assert!(matches!(reader.verify_text_entries(), Err(MemBufferError::InvalidUtf8(_))));
+        assert_eq!(reader.load_entry::<&str>(2).unwrap(), "Moon");
+        assert_eq!(unsafe{reader.load_str_unchecked(2)}.unwrap(), "Moon");
+        assert!(unsafe{reader.load_str_unchecked(1)}.is_err());
+    }
+
     #[test]
     fn check_serialize_vecu8_deserialize() {
         let mut writer = MemBufferWriter::new();
         let some_bytes : Vec<u8> = vec![100,200,100,200,1,2,3,4,5,6,7,8,9,10];
         writer.add_entry(&some_bytes[..]);
-        let result = writer.finalize();
+        let result = writer.finalize().unwrap();
 
         let reader = MemBufferReader::new(&result).unwrap();
         assert_eq!(reader.load_entry::<&[u8]>(0).unwrap(), vec![100,200,100,200,1,2,3,4,5,6,7,8,9,10]);
     }
 
-    #[should_panic]
     #[test]
     fn check_wrong_key() {
         let mut writer = MemBufferWriter::new();
         let some_bytes : Vec<u64> = vec![100,200,100,200,1,2,3,4,5,6,7,8,9,10];
         writer.add_entry(&some_bytes[..]);
         writer.add_entry(&some_bytes[..]);
-        let result = writer.finalize();
+        let result = writer.finalize().unwrap();
 
         let reader = MemBufferReader::new(&result).unwrap();
         assert_eq!(reader.load_entry::<&[u64]>(0).unwrap(), vec![100,200,100,200,1,2,3,4,5,6,7,8,9,10]);
-        //TODO check index overflow
-        assert_eq!(reader.load_entry::<&[u64]>(3).unwrap(), vec![100,200,100,200,1,2,3,4,5,6,7,8,9,10]);
+        match reader.load_entry::<&[u64]>(3).unwrap_err() {
+            MemBufferError::KeyOutOfRange { key, len } => {
+                assert_eq!(key, 3);
+                assert_eq!(len, 2);
+            }
+            err => panic!("Unexpected error {}", err)
+        }
+        assert!(reader.load_recursive_reader(2).is_err());
+        #[cfg(feature = "bincode")]
+        assert!(reader.load_serde_entry::<u32>(2).is_err());
+    }
+
+    #[test]
+    fn check_wrong_key_writer() {
+        let mut writer = MemBufferWriter::new();
+        writer.add_entry("earth");
+
+        assert!(matches!(writer.load_entry::<&str>(1), Err(MemBufferError::KeyOutOfRange { key: 1, len: 1 })));
+        assert!(matches!(writer.set_entry("cool", 1), Err(MemBufferError::KeyOutOfRange { key: 1, len: 1 })));
+        assert_eq!(writer.len(), 1);
+        assert_eq!(writer.load_entry::<&str>(0).unwrap(), "earth");
     }
 
     #[test]
@@ -628,12 +5224,12 @@ mod tests {
         writer.add_entry(str1);
         writer.add_entry(str2);
         writer.add_entry(str3);
-        let result = writer.finalize();
+        let result = writer.finalize().unwrap();
 
         let mut writer2 = MemBufferWriter::from(&result).unwrap();
         writer2.add_entry("fuchs");
         
-        let added2 = writer2.finalize();
+        let added2 = writer2.finalize().unwrap();
         let reader = MemBufferReader::new(&added2).unwrap();
         assert_eq!(reader.len(),4);
         assert_eq!(reader.load_entry::<&str>(3).unwrap(),"fuchs");
@@ -646,7 +5242,7 @@ mod tests {
         let some_bytes : Vec<u64> = vec![100,200,100,200,1,2,3,4,5,6,7,8,9,10];
         writer.add_entry(&some_bytes[..]);
         writer.add_entry(&some_bytes[..]);
-        let result = writer.finalize();
+        let result = writer.finalize().unwrap();
 
         let reader = MemBufferReader::new(&result).unwrap();
         assert_eq!(reader.load_entry::<&[u64]>(0).unwrap(), vec![100,200,100,200,1,2,3,4,5,6,7,8,9,10]);
@@ -661,7 +5257,7 @@ mod tests {
         writer.add_entry(&some_bytes[..]);
         writer.add_entry(&some_bytes[..]);
         writer.add_entry(&some_bytes[..]);
-        let result = writer.finalize();
+        let result = writer.finalize().unwrap();
 
         let reader = MemBufferReader::new(&result).unwrap();
         assert_eq!(reader.len(), 3);
@@ -670,7 +5266,7 @@ mod tests {
     #[test]
     fn check_empty() {
         let writer = MemBufferWriter::new();
-        let result = writer.finalize();
+        let result = writer.finalize().unwrap();
         let reader = MemBufferReader::new(&result).unwrap();
         assert_eq!(reader.len(), 0);
         assert_eq!("Found memory buffer with payload size 0",format!("{:?}",reader));
@@ -680,7 +5276,7 @@ mod tests {
     #[test]
     fn check_slice_too_small() {
         let writer = MemBufferWriter::new();
-        let result = writer.finalize();
+        let result = writer.finalize().unwrap();
         let reader = MemBufferReader::new(&result[0..1]);
         assert_eq!(reader.is_err(),true);
         println!("Error: {}",reader.unwrap_err());
@@ -694,7 +5290,7 @@ mod tests {
         writer.add_entry(&some_bytes[..]);
         writer.add_entry(&some_bytes[..]);
         writer.add_entry(&some_bytes[..]);
-        let result = writer.finalize();
+        let result = writer.finalize().unwrap();
 
         let reader = MemBufferReader::new(&result).unwrap();
         assert_eq!(reader.payload_len(), some_bytes.as_bytes().len()*3);
@@ -710,8 +5306,8 @@ mod tests {
         writer2.add_entry(some_bytes);
 
         writer.add_entry(writer2);
-        let result = writer.finalize();
-        assert_eq!(writer.finalize(), result);
+        let result = writer.finalize().unwrap();
+        assert_eq!(writer.finalize().unwrap(), result);
 
         let reader = MemBufferReader::new(&result).unwrap();
         assert_eq!(reader.len(), 2);
@@ -729,7 +5325,7 @@ mod tests {
     fn check_mem_shift() {
         let mut writer = MemBufferWriter::new();
         writer.add_entry("Earth");
-        let result = writer.finalize();
+        let result = writer.finalize().unwrap();
 
         let reader = MemBufferReader::new(&result[1..]);
         assert_eq!(reader.is_err(),true);
@@ -739,8 +5335,8 @@ mod tests {
     fn check_mem_set_entry() {
         let mut writer = MemBufferWriter::new();
         writer.add_entry("earth");
-        writer.set_entry("cool", 0);
-        let result = writer.finalize();
+        writer.set_entry("cool", 0).unwrap();
+        let result = writer.finalize().unwrap();
 
         let reader = MemBufferReader::new(&result).unwrap();
         assert_eq!(reader.load_entry::<&str>(0).unwrap(),"cool");
@@ -759,7 +5355,7 @@ mod tests {
         }
  
 
-        let result = writer.finalize();
+        let result = writer.finalize().unwrap();
 
         let reader = MemBufferReader::new(&result);
         assert_eq!(reader.is_err(),false);
@@ -776,7 +5372,7 @@ mod tests {
     fn check_serialize_i32_deserialize() {
         let mut writer = MemBufferWriter::new();
         writer.add_entry(100);
-        let result = writer.finalize();
+        let result = writer.finalize().unwrap();
 
         let reader = MemBufferReader::new(&result).unwrap();
         assert_eq!(reader.load_entry::<i32>(0).unwrap(), 100);
@@ -799,7 +5395,7 @@ mod bench {
         }
         let mut writer = MemBufferWriter::new();
         writer.add_entry(&huge_string);
-        let result = writer.finalize();
+        let result = writer.finalize().unwrap();
 
         b.iter(|| {
             let reader = MemBufferReader::new(&result).unwrap();
@@ -816,7 +5412,7 @@ mod bench {
         }
         let mut writer = MemBufferWriter::new();
         writer.add_entry(&huge_string);
-        let result = writer.finalize();
+        let result = writer.finalize().unwrap();
 
         b.iter(|| {
             let reader = MemBufferReader::new(&result).unwrap();
@@ -833,7 +5429,7 @@ mod bench {
         }
         let mut writer = MemBufferWriter::new();
         writer.add_entry(&huge_string);
-        let result = writer.finalize();
+        let result = writer.finalize().unwrap();
 
         b.iter(|| {
             let reader = MemBufferReader::new(&result).unwrap();
@@ -852,7 +5448,7 @@ mod bench {
         writer.add_entry(&huge_string);
         writer.add_entry(&huge_string);
         writer.add_entry(&huge_string);
-        let result = writer.finalize();
+        let result = writer.finalize().unwrap();
         assert!(result.len() > 3_000_000);
 
         b.iter(|| {
@@ -876,7 +5472,7 @@ mod bench {
         writer.add_entry(&huge_string);
         writer.add_entry(&huge_string);
         writer.add_entry(&huge_string);
-        let result = writer.finalize();
+        let result = writer.finalize().unwrap();
         assert!(result.len() > 300_000_000);
 
         b.iter(|| {